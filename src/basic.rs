@@ -36,6 +36,26 @@ pub enum FuzzyToken {
     Exist([f32; 5]),
 }
 
+/// How a place combines an arriving token with the one it holds
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MergeStrategy {
+    /// `(old + new) / 2`, later arrivals weigh more, the default as it always was
+    Unite,
+    /// the mean of every token that arrived since the place was empty
+    Average,
+    Overwrite,
+    KeepFirst,
+    Max,
+    Min,
+    Sum,
+}
+
+impl Default for MergeStrategy {
+    fn default() -> MergeStrategy {
+        MergeStrategy::Unite
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum UnifiedToken{
     Phi,
@@ -50,14 +70,12 @@ impl UnifiedToken {
     pub fn from_option(o : Option<f32>) -> UnifiedToken {
         match o {
             None => UnifiedToken::Phi ,
-            Some(v) => {
-                assert!(!v.is_nan());
-                UnifiedToken::Exist(v)},
+            Some(v) => UnifiedToken::Exist(v),
         }
     }
 
+    /// NaN is kept, the executor's fault policy deals with it
     pub fn from_val(val : f32) -> UnifiedToken {
-        assert!(!val.is_nan());
         UnifiedToken::Exist(val)
     }
 
@@ -80,12 +98,54 @@ impl UnifiedToken {
         }
     }
 
+    /// Merges `t` into this token the way `strategy` says,
+    /// `arrivals` counts the tokens merged since the place was last empty
+    pub fn merge(&mut self, t: UnifiedToken, strategy: MergeStrategy, arrivals: &mut u32) {
+        let v = match t {
+            UnifiedToken::Phi => return,
+            UnifiedToken::Exist(v) => v,
+        };
+        let old = match *self {
+            UnifiedToken::Phi => {
+                *self = UnifiedToken::Exist(v);
+                *arrivals = 1;
+                return;
+            },
+            UnifiedToken::Exist(old) => old,
+        };
+        let merged = match strategy {
+            MergeStrategy::Unite => (old + v) / 2.0,
+            MergeStrategy::Average => old + (v - old) / (*arrivals + 1) as f32,
+            MergeStrategy::Overwrite => v,
+            MergeStrategy::KeepFirst => old,
+            MergeStrategy::Max => old.max(v),
+            MergeStrategy::Min => old.min(v),
+            MergeStrategy::Sum => old + v,
+        };
+        *self = UnifiedToken::Exist(merged);
+        *arrivals += 1;
+    }
+
     pub fn not_phi(&self) ->bool {
         match *self {
             UnifiedToken::Phi => false,
             UnifiedToken::Exist(_) => true,
         }
     }
+
+    pub fn is_nan(&self) -> bool {
+        match *self {
+            UnifiedToken::Phi => false,
+            UnifiedToken::Exist(v) => v.is_nan(),
+        }
+    }
+
+    pub fn weighted(&self, weight: f32) -> UnifiedToken {
+        match *self {
+            UnifiedToken::Phi => UnifiedToken::Phi,
+            UnifiedToken::Exist(v) => UnifiedToken::Exist(v * weight),
+        }
+    }
 }
 
 impl FuzzyToken {
@@ -128,6 +188,19 @@ impl FuzzyToken {
     }
 
 
+    pub fn weighted(&self, weight: f32) -> FuzzyToken {
+        match *self {
+            Phi => Phi,
+            Exist(ref arr) => {
+                let mut map = [0.0; 5];
+                for i in 0..5 {
+                    map[i] = arr[i] * weight;
+                }
+                Exist(map)
+            }
+        }
+    }
+
     pub fn get_val(&self, fv : FuzzyValue) -> f32 {
         match *self {
             Phi => 0.0,
@@ -298,10 +371,31 @@ impl Defuzzyfier for TriangleFuzzyfier {
 
 #[cfg(test)]
 mod tests {
-    use super::{FuzzyToken,  TriangleFuzzyfier, Fuzzyfier, Defuzzyfier, UnifiedToken} ;
+    use super::{FuzzyToken,  TriangleFuzzyfier, Fuzzyfier, Defuzzyfier, UnifiedToken, MergeStrategy} ;
     use super::FuzzyToken::*;
     use super::FuzzyValue::*;
 
+    #[test]
+    fn merge_test(){
+        let merged = |strategy: MergeStrategy| {
+            let mut tk = UnifiedToken::Phi;
+            let mut arrivals = 0;
+            for v in &[3.0, 6.0, 0.0] {
+                tk.merge(UnifiedToken::Exist(*v), strategy, &mut arrivals);
+            }
+            tk.merge(UnifiedToken::Phi, strategy, &mut arrivals);
+            assert_eq!(arrivals, 3);
+            tk
+        };
+        assert_eq!(merged(MergeStrategy::Unite), UnifiedToken::Exist(2.25));
+        assert_eq!(merged(MergeStrategy::Average), UnifiedToken::Exist(3.0));
+        assert_eq!(merged(MergeStrategy::Overwrite), UnifiedToken::Exist(0.0));
+        assert_eq!(merged(MergeStrategy::KeepFirst), UnifiedToken::Exist(3.0));
+        assert_eq!(merged(MergeStrategy::Max), UnifiedToken::Exist(6.0));
+        assert_eq!(merged(MergeStrategy::Min), UnifiedToken::Exist(0.0));
+        assert_eq!(merged(MergeStrategy::Sum), UnifiedToken::Exist(9.0));
+    }
+
     #[test]
     fn zero_scale_test(){
         let ff = TriangleFuzzyfier::with_min_max(0.0*-1.0, 0.0);
@@ -398,6 +492,15 @@ mod tests {
         assert_eq!(phi, UnifiedToken::Exist(0.0));
     }
 
+    #[test]
+    fn weighted_test() {
+        let ft = FuzzyToken::from_arr([0.0, 0.5, 0.5, 0.0, 0.0]);
+        assert_eq!(ft.weighted(0.5), Exist([0.0, 0.25, 0.25, 0.0, 0.0]));
+        assert_eq!(Phi.weighted(0.5), Phi);
+        assert_eq!(UnifiedToken::from_val(3.0).weighted(0.5), UnifiedToken::Exist(1.5));
+        assert_eq!(UnifiedToken::Phi.weighted(0.5), UnifiedToken::Phi);
+    }
+
     #[test]
     fn unified_token_test_as_option() {
         let  phi =UnifiedToken::Phi;
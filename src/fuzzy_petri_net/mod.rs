@@ -0,0 +1,7 @@
+mod net_builder;
+mod net_executor;
+
+pub use self::net_builder::{FuzzyPetriNet, FuzzyPetriNetBuilder, FuzzyTableE};
+pub use self::net_builder::{FuzzyEventManager, FuzzyTokenConsumer};
+pub use self::net_executor::{SynchronousFuzzyPetriExecutor, AsynchronousFuzzyPetriExecutor,
+    AsynchronousThreadedFuzzyPetriExecutor, FuzzyAsyncExecutorMsg, FuzzyExecutorGuard};
@@ -0,0 +1,307 @@
+use tables::*;
+use basic::*;
+use net_structure::NetStructure;
+use unified_petri_net::StructuralProblem;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+#[allow(non_camel_case_types)]
+pub enum FuzzyTableE{
+    oxo(OneXOneTable),
+    oxt(OneXTwoTable),
+    txo(TwoXOneTable),
+    txt(TwoXTwoTable),
+}
+
+impl FuzzyTableE {
+    pub fn get_table(&self) -> &ExecutableFuzzyTable {
+        match *self {
+          FuzzyTableE::oxo(ref t) => t,
+          FuzzyTableE::oxt(ref t) => t,
+          FuzzyTableE::txo(ref t) => t,
+          FuzzyTableE::txt(ref t) => t,
+        }
+    }
+
+    pub fn input_nr(&self) -> usize {
+        match *self {
+          FuzzyTableE::oxo(_) | FuzzyTableE::oxt(_) => 1,
+          FuzzyTableE::txo(_) | FuzzyTableE::txt(_) => 2,
+        }
+    }
+
+    pub fn output_nr(&self) -> usize {
+        match *self {
+          FuzzyTableE::oxo(_) | FuzzyTableE::txo(_) => 1,
+          FuzzyTableE::oxt(_) | FuzzyTableE::txt(_) => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trans{
+    delay: i32,
+    table: FuzzyTableE,
+    is_out: bool,
+    before_places: Vec<usize>,
+    before_weights: Vec<f32>,
+    after_places: Vec<usize>,
+}
+
+impl Trans {
+    /// The arcs the table of this transition does not fit
+    fn table_problems(&self, tr_id: usize) -> Vec<StructuralProblem> {
+        let mut problems = vec![];
+        let expected_inps = self.table.input_nr();
+        if self.before_places.len() != expected_inps {
+            problems.push(StructuralProblem::WrongInputNr{
+                tr_id: tr_id, expected: expected_inps, found: self.before_places.len()});
+        }
+        let expected_outs = if self.is_out { 0 } else { self.table.output_nr() };
+        if self.after_places.len() != expected_outs {
+            problems.push(StructuralProblem::WrongOutputNr{
+                tr_id: tr_id, expected: expected_outs, found: self.after_places.len()});
+        }
+        problems
+    }
+
+    pub fn new(delay : i32, table: FuzzyTableE, out : bool) -> Trans{
+         Trans{
+            delay: delay,
+            is_out: out,
+            table: table,
+            before_places : vec![],
+            before_weights : vec![],
+            after_places : vec![],
+        }
+
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Place{
+    is_inp: bool,
+    inital_marking: FuzzyToken,
+    before_transition: Vec<usize>,
+    after_transition: Vec<usize>,
+}
+
+impl Place {
+    pub fn new(inp: bool) -> Place {
+        Place {
+            is_inp :inp,
+            inital_marking : FuzzyToken::Phi,
+            before_transition : vec![],
+            after_transition : vec![],
+        }
+    }
+
+}
+
+pub trait FuzzyTokenConsumer : Send{
+    fn consume(&mut self, ft: FuzzyToken);
+}
+
+pub struct FuzzyEventManager {
+    handlers: HashMap<usize, Vec<Box<FuzzyTokenConsumer>>>,
+}
+
+impl FuzzyEventManager {
+    pub fn new() -> FuzzyEventManager {
+        FuzzyEventManager {
+            handlers: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, tr_id: usize, f :Box<FuzzyTokenConsumer>) {
+        self.handlers.entry(tr_id).or_insert(vec![]).push(f);
+    }
+
+    pub fn execute_handler(&mut self, tr_id: usize, ft: FuzzyToken) {
+        if let Some(my_handlers) = self.handlers.get_mut(&tr_id) {
+            for f in (*my_handlers).iter_mut() {
+                f.consume(ft.clone());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzyPetriNet {
+    transitions : Vec<Trans>,
+    places : Vec<Place>,
+}
+
+pub struct FuzzyPetriNetBuilder {
+    transitions : Vec<Trans>,
+    places : Vec<Place>,
+    manager: FuzzyEventManager,
+}
+
+impl FuzzyPetriNetBuilder {
+
+    pub fn new() -> FuzzyPetriNetBuilder {
+        FuzzyPetriNetBuilder {
+            transitions : vec![],
+            places: vec![],
+            manager: FuzzyEventManager::new(),
+        }
+    }
+
+    pub fn add_transition(&mut self, delay : i32,  table: FuzzyTableE ) -> usize {
+        self.transitions.push(Trans::new(delay, table, false ));
+        self.transitions.len() - 1
+    }
+
+    pub fn add_out_transition(&mut self,  table: FuzzyTableE ) -> usize {
+        match table {
+            FuzzyTableE::oxo(_) => {/* nothing */},
+            _ => panic!("wrong table type for out transition")
+        }
+        self.transitions.push(Trans::new(0, table, true));
+        self.transitions.len() - 1
+    }
+
+    pub fn add_place(&mut self) -> usize {
+        self.places.push(Place::new(false));
+        self.places.len() -1
+    }
+
+    pub fn add_inp_place(&mut self) -> usize {
+        self.places.push(Place::new(true));
+        self.places.len() -1
+    }
+
+    pub fn connect_transition_with_place(&mut self, tr : usize, pl : usize)  {
+        self.transitions[tr].after_places.push(pl);
+        self.places[pl].before_transition.push(tr);
+    }
+
+    pub fn connect_place_with_transition(&mut self,  pl : usize, tr: usize)  {
+        self.connect_place_with_transition_with_weight(pl, tr, 1.0);
+    }
+
+    pub fn connect_place_with_transition_with_weight(&mut self,  pl : usize, tr: usize,
+                                                     weight: f32)  {
+        self.transitions[tr].before_places.push(pl);
+        self.transitions[tr].before_weights.push(weight);
+        self.places[pl].after_transition.push(tr);
+    }
+
+    pub fn set_innitial_marking(&mut self, pl: usize, token : FuzzyToken ) {
+        self.places[pl].inital_marking = token;
+    }
+
+    pub fn add_action_for_out_trans(&mut self, tr_id: usize, f : Box<FuzzyTokenConsumer>) {
+        self.manager.add(tr_id, f);
+    }
+
+    /// Every transition whose arcs do not fit its table, empty if the net can be built
+    pub fn validate(&self) -> Vec<StructuralProblem> {
+        self.transitions.iter().enumerate()
+            .flat_map(|(tr_id, tr)| tr.table_problems(tr_id))
+            .collect()
+    }
+
+    pub fn build(self) -> Result<(FuzzyPetriNet, FuzzyEventManager), Vec<StructuralProblem>> {
+        let problems = self.validate();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+        let net = FuzzyPetriNet{
+            places : self.places,
+            transitions : self.transitions,
+        };
+        Ok((net, self.manager))
+    }
+}
+
+impl FuzzyPetriNet {
+
+    #[inline]
+    pub fn get_place_nr(&self ) -> usize {
+        self.places.len()
+    }
+
+    #[inline]
+    pub fn get_trans_nr(&self ) -> usize {
+        self.transitions.len()
+    }
+
+    #[inline]
+    pub fn is_trans_out(&self, tr_id: usize) -> bool {
+        self.transitions[tr_id].is_out
+    }
+
+    #[inline]
+    pub fn is_place_inp(&self, pl_id: usize) -> bool {
+        self.places[pl_id].is_inp
+    }
+
+    #[inline]
+    pub fn table_for_trans(&self, tr_id: usize) ->&ExecutableFuzzyTable {
+        self.transitions[tr_id].table.get_table()
+    }
+
+    #[inline]
+    pub fn typed_table_for_trans(&self, tr_id: usize) ->&FuzzyTableE {
+        &self.transitions[tr_id].table
+    }
+
+    #[inline]
+    pub fn get_places_after_trans(&self, tr_id: usize) -> &Vec<usize>{
+        &self.transitions[tr_id].after_places
+    }
+
+    #[inline]
+    pub fn get_tanss_after_place(&self, pl_id: usize) -> &Vec<usize>{
+        &self.places[pl_id].after_transition
+    }
+
+    #[inline]
+    pub fn get_places_befor_trans(&self, tr_id: usize) -> &Vec<usize> {
+        &self.transitions[tr_id].before_places
+    }
+
+    #[inline]
+    pub fn get_weights_befor_trans(&self, tr_id: usize) -> &Vec<f32> {
+        &self.transitions[tr_id].before_weights
+    }
+
+    #[inline]
+    pub fn get_initial_marking(&self, pl_id: usize) -> FuzzyToken {
+        self.places[pl_id].inital_marking.clone()
+    }
+
+    #[inline]
+    pub fn get_delay(&self, tr_id: usize) -> i32 {
+        self.transitions[tr_id].delay
+    }
+
+}
+
+impl NetStructure for FuzzyPetriNet {
+    fn get_trans_nr(&self) -> usize {
+        FuzzyPetriNet::get_trans_nr(self)
+    }
+
+    fn get_places_befor_trans(&self, tr_id: usize) -> &Vec<usize> {
+        FuzzyPetriNet::get_places_befor_trans(self, tr_id)
+    }
+
+    fn is_place_inp(&self, pl_id: usize) -> bool {
+        FuzzyPetriNet::is_place_inp(self, pl_id)
+    }
+
+    fn is_trans_out(&self, tr_id: usize) -> bool {
+        FuzzyPetriNet::is_trans_out(self, tr_id)
+    }
+
+    fn has_delay(&self, tr_id: usize) -> bool {
+        self.get_delay(tr_id) != 0
+    }
+
+    fn possibly_executable(&self, tr_id: usize, marked: &Vec<bool>) -> bool {
+        self.table_for_trans(tr_id).possibly_executable(marked)
+    }
+}
@@ -0,0 +1,469 @@
+extern crate fnv;
+extern crate timer;
+extern crate chrono;
+
+
+
+use fuzzy_petri_net::net_builder::{FuzzyPetriNet, FuzzyEventManager};
+use basic::*;
+use net_structure::{order_of_transitions, possibly_executable_trans, DEFAULT_MAX_LOOP};
+use std::mem;
+use std::collections::HashMap;
+use self::fnv::FnvHasher;
+use std::hash::BuildHasherDefault;
+use std::sync::mpsc::*;
+use std::thread;
+
+
+use self::timer::*;
+use self::chrono::Duration;
+
+type MyHasher = BuildHasherDefault<FnvHasher>;
+
+struct BasicFuzzyPetriExecutor {
+    net: FuzzyPetriNet,
+    event_manager: FuzzyEventManager,
+    place_state: Vec<FuzzyToken>,
+    trans_state: Vec<i32>,
+    trans_holds: Vec<Vec<FuzzyToken>>,
+    trans_order: Vec<usize>,
+    cached_possibly_exec : HashMap<Vec<bool>, Vec<usize>,MyHasher>,
+}
+
+impl BasicFuzzyPetriExecutor {
+
+    pub fn new(net : FuzzyPetriNet, men: FuzzyEventManager) -> BasicFuzzyPetriExecutor {
+        BasicFuzzyPetriExecutor{
+            trans_order: order_of_transitions(&net),
+            place_state: init_place_state(&net),
+            trans_state: vec![0; net.get_trans_nr()],
+            trans_holds: vec![vec![]; net.get_trans_nr()],
+            event_manager: men,
+            cached_possibly_exec: HashMap::default(),
+            net: net,
+        }
+    }
+
+    pub fn put_tokens_to_inp_places(&mut self, inps: Vec<(usize, FuzzyToken)>) {
+        for (pl_id, tk) in inps.into_iter() {
+            self.place_state[pl_id].unite(tk);
+        }
+    }
+
+    pub fn update_delay_state(&mut self) {
+        for tr_id in 0..self.net.get_trans_nr() {
+            if self.trans_state[tr_id] > 0 {
+                if self.trans_state[tr_id] == 1{
+                    self.finish_fire(tr_id);
+                }
+                self.trans_state[tr_id] -= 1;
+            }
+        }
+    }
+
+
+    pub fn execute_firable_transitions(&mut self) {
+        let mut loop_cntr= 0;
+        let max_loop = DEFAULT_MAX_LOOP;
+        let mut heappened_something = true;
+        while heappened_something && loop_cntr < max_loop {
+            heappened_something = false;
+            loop_cntr += 1;
+            let pos ={self.get_possible_executable_trans()};
+            for tr_id in pos{
+                match self.is_fireable(tr_id){
+                    None => {/*does notin*/},
+                    Some(inps) => {
+                        heappened_something = true;
+                        self.start_fire(tr_id, inps);
+                        break;
+                    }
+                }
+            }
+        }
+
+    }
+
+    fn finish_fire(&mut self, tr_id: usize) {
+        let mut rez = mem::replace(&mut self.trans_holds[tr_id], vec![]);
+        if self.net.is_trans_out(tr_id) {
+            self.event_manager.execute_handler(tr_id,
+                                               mem::replace(&mut rez[0], FuzzyToken::Phi));
+        } else {
+            // the builder made sure the table has a token for every out place
+            let out_places = self.net.get_places_after_trans(tr_id);
+            for i in 0..rez.len() {
+                 self.place_state[out_places[i]].unite(
+                     mem::replace(&mut rez[i], FuzzyToken::Phi));
+            }
+        }
+
+
+    }
+
+    fn start_fire(&mut self, tr_id: usize, inp_tokens: Vec<FuzzyToken>) {
+
+        self.clear_inp_tokens(tr_id);
+        let rez = self.net.table_for_trans(tr_id).execute(inp_tokens);
+        self.trans_holds[tr_id] = rez;
+        let delay = self.net.get_delay(tr_id);
+        if delay == 0 {
+            self.finish_fire(tr_id);
+        } else {
+            self.trans_state[tr_id] = delay;
+        }
+    }
+
+
+    fn get_inp_token(&self, tr_id: usize) -> Vec<FuzzyToken> {
+        let mut to_ret = vec![];
+        let inp_places = self.net.get_places_befor_trans(tr_id);
+        let weights = self.net.get_weights_befor_trans(tr_id);
+        for (place, weight) in inp_places.iter().zip(weights) {
+            let ft = self.place_state[*place].weighted(*weight);
+            to_ret.push(ft);
+        }
+        to_ret
+    }
+
+    fn clear_inp_tokens(&mut self, tr_id: usize)  {
+        let inp_places = self.net.get_places_befor_trans(tr_id);
+        for place in inp_places {
+            self.place_state[*place] = FuzzyToken::Phi;
+        }
+    }
+
+    fn is_fireable(&self, tr_id: usize) -> Option<Vec<FuzzyToken>>{
+        if self.trans_state[tr_id] != 0{
+            return None;
+        }
+        let inp_tokens = self.get_inp_token(tr_id);
+        if self.net.table_for_trans(tr_id).is_executable(&inp_tokens) {
+            Some(inp_tokens)
+        } else {None}
+    }
+
+    fn get_possible_executable_trans(&mut self) -> Vec<usize>{
+        let sm = self.simplyfied_marking();
+        if !self.cached_possibly_exec.contains_key(&sm) {
+            let v = possibly_executable_trans(&self.net, &self.trans_order, &sm);
+            self.cached_possibly_exec.insert(sm.clone(), v);
+        }
+        return self.cached_possibly_exec.get(&sm).unwrap().clone()
+
+    }
+
+    fn simplyfied_marking(&self) -> Vec<bool>{
+        self.place_state.iter().map(|x| *x != FuzzyToken::Phi).collect()
+    }
+
+
+}
+
+fn init_place_state(net: &FuzzyPetriNet) -> Vec<FuzzyToken>{
+    let mut to_ret = vec![];
+    for place_id in 0..net.get_place_nr(){
+        to_ret.push(net.get_initial_marking(place_id));
+    }
+    to_ret
+}
+
+pub struct SynchronousFuzzyPetriExecutor{
+    basic : BasicFuzzyPetriExecutor,
+}
+
+impl SynchronousFuzzyPetriExecutor {
+    pub fn new(net: FuzzyPetriNet, men: FuzzyEventManager) -> SynchronousFuzzyPetriExecutor {
+        SynchronousFuzzyPetriExecutor {
+            basic: BasicFuzzyPetriExecutor::new(net, men),
+        }
+    }
+
+    pub fn run_tick(&mut self, inps: Vec<(usize, FuzzyToken)>) {
+        self.basic.put_tokens_to_inp_places(inps);
+        self.basic.update_delay_state();
+        self.basic.execute_firable_transitions();
+    }
+}
+pub enum FuzzyAsyncExecutorMsg {
+    Tick,
+    Input(Vec<(usize, FuzzyToken)>),
+    Stop,
+}
+
+pub struct AsynchronousFuzzyPetriExecutor{
+    basic : BasicFuzzyPetriExecutor,
+    tx : Sender<FuzzyAsyncExecutorMsg>,
+    rx : Receiver<FuzzyAsyncExecutorMsg>,
+}
+
+impl AsynchronousFuzzyPetriExecutor {
+    pub fn new(net: FuzzyPetriNet, men: FuzzyEventManager) -> AsynchronousFuzzyPetriExecutor {
+        let (tx, rx) = channel();
+        AsynchronousFuzzyPetriExecutor {
+            basic: BasicFuzzyPetriExecutor::new(net, men),
+            tx : tx,
+            rx : rx,
+        }
+    }
+
+    pub fn get_sender(&self) -> Sender<FuzzyAsyncExecutorMsg> {
+        self.tx.clone()
+    }
+
+    pub fn run(&mut self){
+        loop {
+            let rez = self.rx.recv().unwrap();
+            match rez {
+               FuzzyAsyncExecutorMsg::Tick
+                   => {self.basic.update_delay_state();
+                       self.basic.execute_firable_transitions(); },
+               FuzzyAsyncExecutorMsg::Input(v)
+                   => {self.basic.put_tokens_to_inp_places(v);
+                       self.basic.execute_firable_transitions();},
+               FuzzyAsyncExecutorMsg::Stop => break,
+            }
+        }
+    }
+}
+
+pub struct AsynchronousThreadedFuzzyPetriExecutor{
+    basic : BasicFuzzyPetriExecutor,
+    tx : Sender<FuzzyAsyncExecutorMsg>,
+    rx : Receiver<FuzzyAsyncExecutorMsg>,
+    dur : Duration,
+}
+
+pub struct FuzzyExecutorGuard {
+    _timer_guard: Guard,
+    sender : Sender<FuzzyAsyncExecutorMsg>,
+    _t: Timer,
+}
+
+impl FuzzyExecutorGuard {
+    pub fn stop(self) {/* the drop should resolve it*/}
+}
+
+impl Drop for FuzzyExecutorGuard{
+    fn drop(&mut self) {
+        let _ = self.sender.send(FuzzyAsyncExecutorMsg::Stop);
+    }
+}
+
+impl AsynchronousThreadedFuzzyPetriExecutor {
+    pub fn new(net: FuzzyPetriNet , men: FuzzyEventManager, dur :Duration)
+        -> AsynchronousThreadedFuzzyPetriExecutor {
+
+        let (tx, rx) = channel();
+        AsynchronousThreadedFuzzyPetriExecutor {
+            basic: BasicFuzzyPetriExecutor::new(net, men),
+            tx : tx,
+            rx : rx,
+            dur : dur,
+        }
+    }
+
+    pub fn get_sender(&self) -> Sender<FuzzyAsyncExecutorMsg> {
+        self.tx.clone()
+    }
+
+    pub fn start(self) -> FuzzyExecutorGuard {
+        let timer = Timer::new();
+        let sender = self.get_sender();
+        let guard = timer.schedule_repeating(self.dur, move ||{
+             let _ = sender.send(FuzzyAsyncExecutorMsg::Tick);
+        });
+
+        let exec_guard = FuzzyExecutorGuard {
+            _timer_guard : guard,
+            sender : self.get_sender(),
+            _t : timer,
+        };
+
+        thread::spawn (move || {
+            let mut s = self;
+            loop {
+                let rez = s.rx.recv().unwrap();
+                match rez {
+                   FuzzyAsyncExecutorMsg::Tick=> {
+                       s.basic.update_delay_state();
+                       s.basic.execute_firable_transitions();
+                   },
+                   FuzzyAsyncExecutorMsg::Input(v)
+                       => {s.basic.put_tokens_to_inp_places(v);
+                           s.basic.execute_firable_transitions();},
+                   FuzzyAsyncExecutorMsg::Stop => break,
+                }
+            }
+
+        });
+        exec_guard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use tables::*;
+    use fuzzy_petri_net::net_builder::*;
+    use unified_petri_net::StructuralProblem;
+    use std::sync::*;
+
+    struct History{
+        rez : Vec<(usize,FuzzyToken)>,
+    }
+
+    struct MyConsumer {
+        hist: Arc<RwLock<History>>,
+        tr_id : usize,
+    }
+
+    impl FuzzyTokenConsumer for MyConsumer {
+        fn consume(&mut self, ft: FuzzyToken){
+            self.hist.write().unwrap().rez.push((self.tr_id, ft));
+        }
+    }
+
+    pub struct ConsumerFactory {
+        hist: Arc<RwLock<History>>,
+    }
+
+    impl ConsumerFactory {
+        fn new()-> ConsumerFactory{
+            let hist = History{rez: Vec::new()};
+            ConsumerFactory{
+                hist: Arc::new(RwLock::new(hist)),
+            }
+        }
+
+        fn create_handler_for(&mut self, tr_id :usize ) -> Box<MyConsumer> {
+            Box::new(MyConsumer{hist : self.hist.clone(), tr_id: tr_id})
+        }
+
+        pub fn get_current_hist(&self) -> Vec<(usize, FuzzyToken)> {
+            let mut to_ret = Vec::new();
+            for i in &self.hist.read().unwrap().rez {
+                to_ret.push(i.clone());
+            }
+            to_ret
+        }
+
+    }
+
+    pub fn simple_delay_net() -> (FuzzyPetriNet, FuzzyEventManager, ConsumerFactory) {
+        let mut bld = FuzzyPetriNetBuilder::new();
+
+        let ip0 = bld.add_inp_place();
+        let p0 = bld.add_place();
+        bld.set_innitial_marking(p0, FuzzyToken::zero_token());
+        let t0 = bld.add_transition(1,FuzzyTableE::txt(TwoXTwoTable::default_table()));
+        bld.connect_place_with_transition(p0, t0);
+        bld.connect_place_with_transition(ip0, t0);
+
+        let p1 = bld.add_place();
+        bld.connect_transition_with_place(t0,p1);
+
+        let p2 = bld.add_place();
+        bld.connect_transition_with_place(t0,p2);
+
+        let ot2 = bld.add_out_transition(FuzzyTableE::oxo(OneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, ot2);
+
+        let t1 = bld.add_transition(0, FuzzyTableE::oxo(OneXOneTable::default_table()));
+        bld.connect_place_with_transition(p2,t1);
+        bld.connect_transition_with_place(t1, p0);
+
+
+        let mut consumer_factory = ConsumerFactory::new();
+        let (net, mut event_manager) = bld.build().unwrap();
+        event_manager.add(ot2, consumer_factory.create_handler_for(ot2));
+
+        (net, event_manager, consumer_factory)
+
+    }
+
+    #[test]
+    fn simple_delay_net_test(){
+       let (net, event_manager, cons_fact) =simple_delay_net();
+       let mut exec = SynchronousFuzzyPetriExecutor::new(net, event_manager);
+
+       let inp = vec![(0, FuzzyToken::zero_token())];
+       exec.run_tick(inp);
+
+       let rez = cons_fact.get_current_hist();
+       assert!(rez.len() == 0);
+
+       let inp = vec![];
+       exec.run_tick(inp);
+
+       let rez = cons_fact.get_current_hist();
+       assert_eq!(vec![(1,FuzzyToken::zero_token())],rez);
+
+       let inp = vec![(0, FuzzyToken::from_arr([0.0, 0.0, 0.0, 1.0, 0.0]))];
+       exec.run_tick(inp);
+       exec.run_tick(vec![]);
+
+       let rez = cons_fact.get_current_hist();
+       assert_eq!(vec![(1,FuzzyToken::zero_token()),
+                       (1,FuzzyToken::from_arr([0.0, 0.0, 0.0, 1.0, 0.0]))], rez);
+    }
+
+    #[test]
+    fn asynchronous_executor_stops_test(){
+       let (net, event_manager, cons_fact) =simple_delay_net();
+       let mut exec = AsynchronousFuzzyPetriExecutor::new(net, event_manager);
+       let sender = exec.get_sender();
+
+       sender.send(FuzzyAsyncExecutorMsg::Input(vec![(0, FuzzyToken::zero_token())])).unwrap();
+       sender.send(FuzzyAsyncExecutorMsg::Tick).unwrap();
+       sender.send(FuzzyAsyncExecutorMsg::Stop).unwrap();
+       exec.run();
+
+       assert_eq!(vec![(1,FuzzyToken::zero_token())], cons_fact.get_current_hist());
+    }
+
+    #[test]
+    fn threaded_executor_test(){
+       let (net, event_manager, cons_fact) =simple_delay_net();
+       // the timer would tick only after an hour, the test sends the tick itself
+       let exec = AsynchronousThreadedFuzzyPetriExecutor::new(net, event_manager, Duration::hours(1));
+       let sender = exec.get_sender();
+       let guard = exec.start();
+
+       sender.send(FuzzyAsyncExecutorMsg::Input(vec![(0, FuzzyToken::zero_token())])).unwrap();
+       sender.send(FuzzyAsyncExecutorMsg::Tick).unwrap();
+       let mut waited = 0;
+       while cons_fact.get_current_hist().is_empty() && waited < 500 {
+           thread::sleep(::std::time::Duration::from_millis(10));
+           waited += 1;
+       }
+       guard.stop();
+
+       assert_eq!(vec![(1,FuzzyToken::zero_token())], cons_fact.get_current_hist());
+       // the executor thread drops its receiver once it stopped
+       let mut waited = 0;
+       while sender.send(FuzzyAsyncExecutorMsg::Tick).is_ok() && waited < 500 {
+           thread::sleep(::std::time::Duration::from_millis(10));
+           waited += 1;
+       }
+       assert!(sender.send(FuzzyAsyncExecutorMsg::Tick).is_err());
+    }
+
+    #[test]
+    fn build_rejects_tables_not_fitting_arcs_test(){
+        let mut bld = FuzzyPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place();
+        let p1 = bld.add_place();
+        let t0 = bld.add_transition(0, FuzzyTableE::oxt(OneXTwoTable::default_table()));
+        bld.connect_place_with_transition(ip0, t0);
+        bld.connect_transition_with_place(t0, p1);
+        let ot1 = bld.add_out_transition(FuzzyTableE::oxo(OneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, ot1);
+
+        assert_eq!(bld.validate(), vec![StructuralProblem::WrongOutputNr{tr_id: t0, expected: 2, found: 1}]);
+        assert!(bld.build().is_err());
+    }
+
+
+}
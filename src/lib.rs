@@ -1,4 +1,6 @@
 pub mod basic;
 pub mod tables;
+pub mod net_structure;
 pub mod read_petri;
 pub mod unified_petri_net;
+pub mod fuzzy_petri_net;
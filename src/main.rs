@@ -18,13 +18,13 @@ impl UnifiedTokenConsumer for k{
 }
 
 fn main() {
-    let ww = my_file_read("unified_nets/blink.json");
+    let ww = my_file_read("unified_nets/blink.json").unwrap();
     let rez = deseralize(&ww);
     let bld = rez.unwrap();
 
     let start = precise_time_ns();
 
-    let (net, mut man) = bld.build();
+    let (net, mut man) = bld.build().unwrap();
     man.add(0, Box::new(k{}));
 
     let mut exec = AsynchronousThreadedUnifiedPetriExecutor::
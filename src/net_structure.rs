@@ -0,0 +1,113 @@
+/// How many rounds of firing an executor runs after a tick or an input unless configured otherwise
+pub const DEFAULT_MAX_LOOP: usize = 40;
+
+/// What the executors need to know about the shape of a net,
+/// the unified and the fuzzy nets share the scheduling below through it
+pub trait NetStructure {
+    fn get_trans_nr(&self) -> usize;
+    fn get_places_befor_trans(&self, tr_id: usize) -> &Vec<usize>;
+    fn is_place_inp(&self, pl_id: usize) -> bool;
+    fn is_trans_out(&self, tr_id: usize) -> bool;
+    fn has_delay(&self, tr_id: usize) -> bool;
+
+    fn get_priority(&self, _tr_id: usize) -> i32 {
+        0
+    }
+
+    /// Whether the table of the transition could fire with the given input places marked
+    fn possibly_executable(&self, tr_id: usize, marked: &Vec<bool>) -> bool;
+}
+
+/// The order the executors try the transitions in: the ones reading input places,
+/// the output ones, the undelayed and last the delayed ones, then by descending priority
+pub fn order_of_transitions<N: NetStructure>(net: &N) -> Vec<usize> {
+    let mut inp_trs  = vec![];
+    let mut out_trs  = vec![];
+    let mut nondelay_trs = vec![];
+    let mut delays_trs = vec![];
+    for tr_id in 0..net.get_trans_nr() {
+        let places_needed = net.get_places_befor_trans(tr_id);
+        let mut found = false ;
+        for pl_id in places_needed {
+            if net.is_place_inp(*pl_id) {
+                inp_trs.push(tr_id);
+                found = true;
+                break;
+            }
+        }
+        if ! found {
+            if net.is_trans_out(tr_id) {
+                out_trs.push(tr_id);
+                continue;
+            }
+
+            if !net.has_delay(tr_id) {
+                nondelay_trs.push(tr_id);
+            } else {
+                delays_trs.push(tr_id);
+
+            }
+        }
+    }
+    inp_trs.append(&mut out_trs);
+    inp_trs.append(&mut nondelay_trs);
+    inp_trs.append(&mut delays_trs);
+    // stable, equal priorities keep the order above
+    inp_trs.sort_by_key(|tr_id| -net.get_priority(*tr_id));
+    inp_trs
+}
+
+/// The transitions of `trans_order` whose table could fire on the simplified marking
+pub fn possibly_executable_trans<N: NetStructure>(net: &N, trans_order: &Vec<usize>,
+                                                  simpl_mark : &Vec<bool>) -> Vec<usize> {
+    trans_order.iter()
+        .filter(|tr_id| {
+            let inp = net.get_places_befor_trans(**tr_id).iter().map(|pl| simpl_mark[*pl]).collect();
+            net.possibly_executable(**tr_id, &inp)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shape {
+        before: Vec<Vec<usize>>,
+        inp_places: Vec<usize>,
+        out_trans: Vec<usize>,
+        delayed: Vec<usize>,
+        priorities: Vec<i32>,
+    }
+
+    impl NetStructure for Shape {
+        fn get_trans_nr(&self) -> usize { self.before.len() }
+        fn get_places_befor_trans(&self, tr_id: usize) -> &Vec<usize> { &self.before[tr_id] }
+        fn is_place_inp(&self, pl_id: usize) -> bool { self.inp_places.contains(&pl_id) }
+        fn is_trans_out(&self, tr_id: usize) -> bool { self.out_trans.contains(&tr_id) }
+        fn has_delay(&self, tr_id: usize) -> bool { self.delayed.contains(&tr_id) }
+        fn get_priority(&self, tr_id: usize) -> i32 { self.priorities[tr_id] }
+        fn possibly_executable(&self, _tr_id: usize, marked: &Vec<bool>) -> bool {
+            marked.iter().all(|m| *m)
+        }
+    }
+
+    #[test]
+    fn order_and_possibly_executable_test() {
+        let mut shape = Shape {
+            before: vec![vec![1], vec![2], vec![0], vec![3], vec![1, 2]],
+            inp_places: vec![0],
+            out_trans: vec![3],
+            delayed: vec![0],
+            priorities: vec![0; 5],
+        };
+        assert_eq!(order_of_transitions(&shape), vec![2, 3, 1, 4, 0]);
+        shape.priorities[0] = 1;
+        let order = order_of_transitions(&shape);
+        assert_eq!(order, vec![0, 2, 3, 1, 4]);
+
+        let marked = vec![false, true, true, false];
+        assert_eq!(possibly_executable_trans(&shape, &order, &marked), vec![0, 1, 4]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use basic::*;
+use tables::*;
+use fuzzy_petri_net::{FuzzyPetriNetBuilder, FuzzyTableE};
+
+use super::unified_petr_json_reader::*;
+use super::unified_petr_json_reader::NotExpectedJsonFormat::*;
+
+extern crate rustc_serialize;
+use self::rustc_serialize::json::Json;
+use std::mem;
+
+static TBL_TYPE: &'static str = "type";
+static TBL_DATA: &'static str = "data";
+static IS_PHI: &'static str = "phi";
+static FUZZY_VALS: &'static str = "fuzzyValues";
+
+pub fn deseralize_fuzzy(what :&str)-> Result<FuzzyPetriNetBuilder>  {
+     let obj = &parse_root(what)?;
+
+     let tr_nr = mine!(obj, as_u64, TR_NR) as usize;
+     let pl_nr = mine!(obj, as_u64, PL_NR) as usize;
+
+     let inp_pl_jsons = mine!(obj, as_array, INP_PL);
+     let inp_pl = mine_bool_vec(inp_pl_jsons, INP_PL)?;
+     assert_length!(inp_pl, pl_nr, INP_PL);
+
+     let out_tr_jsons = mine!(obj, as_array, OUT_TR);
+     let out_tr = mine_bool_vec(out_tr_jsons, OUT_TR)?;
+     assert_length!(out_tr, tr_nr, OUT_TR);
+
+     let inital_marking_jsons = mine!(obj, as_array, INIT_PL);
+     let mut init_marking  = mine_fuzzy_init_marking(inital_marking_jsons)?;
+     assert_length!(init_marking, pl_nr, INIT_PL);
+
+     let tr_to_pl_jsons  = mine!(obj, as_array, TR_TO_PL);
+     let tr_to_pl = mine_arcs(tr_to_pl_jsons, pl_nr, TR_TO_PL)?;
+     assert_length!(tr_to_pl, tr_nr, TR_TO_PL);
+
+     let pl_needed_jsons  = mine!(obj, as_array, PLACES_NEED);
+     let pl_needed = mine_arcs(pl_needed_jsons, pl_nr, PLACES_NEED)?;
+     assert_length!(pl_needed, tr_nr, PLACES_NEED);
+
+     let delay_jsons = mine!(obj, as_array, DELAY);
+     let delays = mine_delays(delay_jsons, DELAY)?;
+     assert_length!(delays, tr_nr, DELAY);
+
+     let table_jsons  = mine!(obj, as_array, TBL_TRS);
+     let mut tables = mine_fuzzy_tables(table_jsons)?;
+     assert_length!(tables, tr_nr, TBL_TRS);
+
+     let weights = mine_weights(obj)?;
+
+     let mut bld = FuzzyPetriNetBuilder::new();
+
+     for tr_id in 0..tr_nr {
+         if out_tr[tr_id] {
+             bld.add_out_transition(extact_from_vec(&mut tables, tr_id));
+         } else {
+             bld.add_transition(delays[tr_id] as i32, extact_from_vec(&mut tables, tr_id));
+         }
+     }
+
+     for pl_id in 0..pl_nr {
+         if inp_pl[pl_id] {
+             bld.add_inp_place();
+         } else {
+             bld.add_place();
+         }
+         bld.set_innitial_marking(pl_id, mem::replace(&mut init_marking[pl_id], FuzzyToken::Phi));
+     }
+
+     for tr_id in 0..tr_to_pl.len() {
+         for pl_id in &tr_to_pl[tr_id]{
+             bld.connect_transition_with_place(tr_id, *pl_id);
+         }
+     }
+
+     for tr_id in 0..pl_needed.len() {
+         for pl_id in &pl_needed[tr_id]{
+             let weight = *weights.get(&(*pl_id, tr_id)).unwrap_or(&1.0);
+             bld.connect_place_with_transition_with_weight(*pl_id, tr_id, weight);
+         }
+     }
+
+     Ok(bld)
+}
+
+fn extact_from_vec(vec :&mut Vec<FuzzyTableE>, tr_id:usize) -> FuzzyTableE {
+    let replace_with = FuzzyTableE::oxo(OneXOneTable::default_table());
+    mem::replace(&mut vec[tr_id], replace_with)
+}
+
+fn mine_fuzzy_tables(table_jsons: &Vec<Json>) ->Result<Vec<FuzzyTableE>> {
+    let mut to_ret = Vec::new();
+    for (tr_id, table_json) in table_jsons.iter().enumerate() {
+        let path = &index_path(TBL_TRS, tr_id);
+        let table_obj = table_json.as_object()
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected object".to_string()))?;
+        let table_type = mine!(table_obj, as_string, TBL_TYPE, path);
+        let table_data_obj : &BTreeMap<String, Json> = mine!(table_obj, as_object, TBL_DATA, path);
+        let data_path = &key_path(path, TBL_DATA);
+        let table = match table_type.as_ref() {
+            "1x1" => FuzzyTableE::oxo(OneXOneTable::from_arr(mine_oxo_arr(table_data_obj, data_path)?)),
+            "2x1" => FuzzyTableE::txo(TwoXOneTable::from_arr(mine_txo_arr(table_data_obj, data_path)?)),
+            "1x2" => FuzzyTableE::oxt(OneXTwoTable::from_arr(mine_oxt_arr(table_data_obj, data_path)?)),
+            "2x2" => FuzzyTableE::txt(TwoXTwoTable::from_arr(mine_txt_arr(table_data_obj, data_path)?)),
+            _     => {return Err(WrongJsonValue(key_path(path, TBL_TYPE),
+                                                format!("unknown table type \"{}\"", table_type)))}
+        };
+        to_ret.push(table);
+    }
+    Ok(to_ret)
+}
+
+fn mine_fuzzy_init_marking(inital_markings: &Vec<Json>) -> Result<Vec<FuzzyToken>> {
+    let mut to_ret = Vec::new();
+    for (pl_id, js_val) in inital_markings.iter().enumerate() {
+        let path = &index_path(INIT_PL, pl_id);
+        let val = js_val.as_object()
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected object".to_string()))?;
+        let phi = mine!(val, as_boolean, IS_PHI, path);
+        if phi {
+            to_ret.push(FuzzyToken::Phi);
+        } else {
+            let values = mine!(val, as_array, FUZZY_VALS, path);
+            let vals_path = key_path(path, FUZZY_VALS);
+            if values.len() != 5 {return Err(WrongNumberOfStuff(vals_path, 5, values.len()))};
+            let mut arr = [0.0; 5];
+            for i in 0..5 {
+                arr[i] = values[i].as_f64()
+                    .ok_or_else(|| WrongJsonValue(index_path(&vals_path, i), "expected f64".to_string()))? as f32;
+            }
+            to_ret.push(FuzzyToken::from_arr(arr));
+        }
+    }
+    Ok(to_ret)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn all_inputs_are_readable(){
+        let mut file_cntr = 0;
+        for entry in fs::read_dir("inputs").unwrap() {
+            let path = entry.unwrap().path();
+            let ww = my_file_read(path.to_str().unwrap()).unwrap();
+            let rez = deseralize_fuzzy(&ww);
+            assert!(rez.is_ok(), "could not read {:?}", path);
+            file_cntr += 1;
+        }
+        assert_eq!(file_cntr, 6);
+    }
+
+    #[test]
+    fn simple_delay_read(){
+        let ww = my_file_read("inputs/SimpleDelayPetriNet.json").unwrap();
+        let bld = deseralize_fuzzy(&ww).unwrap();
+        let (net, _) = bld.build().unwrap();
+        assert_eq!(net.get_place_nr(), 4);
+        assert_eq!(net.get_trans_nr(), 3);
+        assert_eq!(net.get_delay(1), 1);
+        assert!(net.is_place_inp(2));
+        assert!(net.is_trans_out(2));
+        assert_eq!(net.get_places_befor_trans(0), &vec![2, 0]);
+        assert_eq!(net.get_initial_marking(0), FuzzyToken::zero_token());
+        assert_eq!(net.get_weights_befor_trans(0), &vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn weights_are_read(){
+        let ww = my_file_read("inputs/SimpleDelayPetriNet.json").unwrap();
+        let changed = ww.replace("\"3\": {\n      \"2\": 1.0", "\"3\": {\n      \"2\": 0.25");
+        assert!(changed != ww);
+        let (net, _) = deseralize_fuzzy(&changed).unwrap().build().unwrap();
+        assert_eq!(net.get_weights_befor_trans(2), &vec![0.25]);
+    }
+
+    #[test]
+    fn unified_format_is_rejected(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        assert_eq!(deseralize_fuzzy(&ww).err(), Some(JsonKeyNotFound("initialMarkingOfThePlaces[0].phi".to_string())));
+    }
+
+    #[test]
+    fn malformed_input_is_an_error(){
+        assert!(deseralize_fuzzy("{\"transitionCntr\": ").is_err());
+        let ww = my_file_read("inputs/SimpleDelayPetriNet.json").unwrap();
+        let short_token = ww.replacen("\"fuzzyValues\": [\n        0.0,", "\"fuzzyValues\": [", 1);
+        assert!(short_token != ww);
+        let err = deseralize_fuzzy(&short_token).err().unwrap();
+        assert!(format!("{}", err).ends_with("].fuzzyValues: expected 5 elements, found 4"), "{}", err);
+    }
+
+}
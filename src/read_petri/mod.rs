@@ -1,3 +1,8 @@
+#[macro_use]
 mod unified_petr_json_reader;
+mod fuzzy_petri_json_reader;
+mod unified_petr_json_writer;
 
 pub use self::unified_petr_json_reader::{deseralize, my_file_read, NotExpectedJsonFormat};
+pub use self::fuzzy_petri_json_reader::deseralize_fuzzy;
+pub use self::unified_petr_json_writer::{seralize, my_file_write};
@@ -1,13 +1,15 @@
 use std;
 use std::fmt;
+use std::io;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 
 use basic::*;
 use tables::*;
-use unified_petri_net::{UnifiedPetriNetBuilder,UnifiedTableE};
+use unified_petri_net::{UnifiedPetriNetBuilder,UnifiedTableE,StructuralProblem};
 
 use self::NotExpectedJsonFormat::*;
 
@@ -15,11 +17,16 @@ extern crate rustc_serialize;
 use self::rustc_serialize::json::Json;
 use std::mem;
 
-#[derive(Debug)]
+/// Every variant carries the path of the offending json element,
+/// e.g. `tableForTransition[3].unifiedData.table.ruleTable.PM.NL`
+#[derive(Debug, PartialEq)]
 pub enum NotExpectedJsonFormat {
-    JsonKeyNotFound(&'static str),
-    WrongNumberOfStuff(&'static str),
-    WrongJsonValue(&'static str),
+    InvalidJson(String),
+    JsonKeyNotFound(String),
+    WrongNumberOfStuff(String, usize, usize),
+    WrongJsonValue(String, String),
+    /// the problems and their descriptions using the names of the nodes
+    InvalidNet(Vec<StructuralProblem>, Vec<String>),
 }
 
 pub type Result<T> = std::result::Result<T, NotExpectedJsonFormat>;
@@ -27,61 +34,107 @@ pub type Result<T> = std::result::Result<T, NotExpectedJsonFormat>;
 impl fmt::Display for NotExpectedJsonFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            JsonKeyNotFound(ref key) =>
-                write!(f, "Json key not found {}", key),
-            WrongNumberOfStuff(ref key) =>
-                write!(f, "wrong number of stuff {}", key ),
-            WrongJsonValue(ref key) =>
-                write!(f, "wrong json value {}", key),
-
+            InvalidJson(ref msg) =>
+                write!(f, "invalid json: {}", msg),
+            JsonKeyNotFound(ref path) =>
+                write!(f, "{}: json key not found", path),
+            WrongNumberOfStuff(ref path, expected, found) =>
+                write!(f, "{}: expected {} elements, found {}", path, expected, found),
+            WrongJsonValue(ref path, ref msg) =>
+                write!(f, "{}: {}", path, msg),
+            InvalidNet(_, ref messages) => {
+                write!(f, "invalid net:")?;
+                for problem in messages {
+                    write!(f, " {};", problem)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
+impl std::error::Error for NotExpectedJsonFormat {
+    fn description(&self) -> &str {
+        "not expected json format"
+    }
+}
 
-pub fn my_file_read(fname: &str) -> String {
+pub fn my_file_read(fname: &str) -> io::Result<String> {
     let path = Path::new(fname);
-    let mut f = File::open(&path)
-        .expect("File reading problem");
+    let mut f = File::open(&path)?;
     let mut s = String::new();
-    f.read_to_string(&mut s).expect("Somthing went reaaly wrong");
-    s
-}
-
-static TR_NR: &'static str = "transitionCntr";
-static PL_NR: &'static str = "placeCntr";
-static INP_PL: &'static str = "isInputPlaces";
-static OUT_TR: &'static str = "isOutputTransition";
-static INIT_PL: &'static str = "initialMarkingOfThePlaces";
-static IS_PHI: &'static str = "isPhi";
-static TOKNE_VAL: &'static str = "val";
-static SCALES: &'static str = "scaleForPlace";
-static TR_TO_PL: &'static str = "fromTransToPlace";
-static TBL_TRS: &'static str = "tableForTransition";
-static TBL_TYPE: &'static str = "unfiedType";
-static TBL_DATA: &'static str = "unifiedData";
-static DELAY: &'static str = "delayForTransition";
-static PLACES_NEED: &'static str = "placesNeededForTrans";
-
-static TABEL: &'static str = "table";
-static OPERATOR: &'static str = "op";
+    f.read_to_string(&mut s)?;
+    Ok(s)
+}
+
+pub static TR_NR: &'static str = "transitionCntr";
+pub static PL_NR: &'static str = "placeCntr";
+pub static INP_PL: &'static str = "isInputPlaces";
+pub static OUT_TR: &'static str = "isOutputTransition";
+pub static INIT_PL: &'static str = "initialMarkingOfThePlaces";
+pub static IS_PHI: &'static str = "isPhi";
+pub static TOKNE_VAL: &'static str = "val";
+pub static SCALES: &'static str = "scaleForPlace";
+pub static TR_TO_PL: &'static str = "fromTransToPlace";
+pub static PL_TO_TR: &'static str = "fromPlaceToTrans";
+pub static TBL_TRS: &'static str = "tableForTransition";
+pub static TBL_TYPE: &'static str = "unfiedType";
+pub static TBL_DATA: &'static str = "unifiedData";
+pub static DELAY: &'static str = "delayForTransition";
+pub static DELAY_MULT: &'static str = "delayMulitiperForTransition";
+pub static PRIORITIES: &'static str = "priorityForTransition";
+pub static MERGE: &'static str = "mergeStrategyForPlace";
+pub static PLACES_NEED: &'static str = "placesNeededForTrans";
+pub static WEIGHTS: &'static str = "weights";
+pub static PL_NAMES: &'static str = "placeNames";
+pub static TR_NAMES: &'static str = "transitionNames";
+pub static PL_DESCS: &'static str = "placeDescriptions";
+pub static TR_DESCS: &'static str = "transitionDescriptions";
+
+pub static TABEL: &'static str = "table";
+pub static OPERATOR: &'static str = "op";
+
+pub fn key_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+pub fn index_path(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
 
 macro_rules! mine {
     ($obj:ident, $fnc:ident, $idd: ident ) => {
-         $obj.get($idd).ok_or(JsonKeyNotFound($idd))?
-             .$fnc().ok_or(WrongJsonValue($idd))?;
+        mine!($obj, $fnc, $idd, "")
+    };
+    ($obj:ident, $fnc:ident, $idd: ident, $path: expr ) => {
+         $obj.get($idd).ok_or_else(|| JsonKeyNotFound(key_path($path, $idd)))?
+             .$fnc().ok_or_else(|| WrongJsonValue(key_path($path, $idd),
+                                                  format!("expected {}", &stringify!($fnc)[3..])))?
     }
 }
 
 macro_rules! assert_length {
     ($vecc: ident, $len: ident, $err_hint: ident ) => {
-            if $vecc.len() != $len {return Err(WrongNumberOfStuff($err_hint))};
+            if $vecc.len() != $len {
+                return Err(WrongNumberOfStuff($err_hint.to_string(), $len, $vecc.len()))
+            };
+    }
+}
+
+pub fn parse_root(what :&str) -> Result<BTreeMap<String, Json>> {
+    let data = Json::from_str(what).map_err(|err| InvalidJson(format!("{}", err)))?;
+    match data {
+        Json::Object(obj) => Ok(obj),
+        _ => Err(WrongJsonValue("root".to_string(), "expected object".to_string())),
     }
 }
 
 pub fn deseralize(what :&str)-> Result<UnifiedPetriNetBuilder>  {
-     let data = Json::from_str(what).unwrap();
-     let obj = data.as_object().unwrap();
+     let obj = &parse_root(what)?;
 
      let tr_nr = mine!(obj, as_u64, TR_NR) as usize;
      let pl_nr = mine!(obj, as_u64, PL_NR) as usize;
@@ -99,25 +152,53 @@ pub fn deseralize(what :&str)-> Result<UnifiedPetriNetBuilder>  {
      assert_length!(init_marking, pl_nr, INIT_PL);
 
      let tr_to_pl_jsons  = mine!(obj, as_array, TR_TO_PL);
-     let tr_to_pl = mine_arcs(tr_to_pl_jsons, TR_TO_PL)?;
+     let tr_to_pl = mine_arcs(tr_to_pl_jsons, pl_nr, TR_TO_PL)?;
      assert_length!(tr_to_pl, tr_nr, TR_TO_PL);
 
      let pl_needed_jsons  = mine!(obj, as_array, PLACES_NEED);
-     let pl_needed = mine_arcs(pl_needed_jsons, PLACES_NEED)?;
+     let pl_needed = mine_arcs(pl_needed_jsons, pl_nr, PLACES_NEED)?;
      assert_length!(pl_needed, tr_nr, PLACES_NEED);
 
      let scales_json = mine!(obj, as_array, SCALES);
      let scales = mine_scale(scales_json, SCALES)?;
+     assert_length!(scales, pl_nr, SCALES);
 
      let delay_jsons = mine!(obj, as_array, DELAY);
      let delays = mine_delays(delay_jsons, DELAY)?;
      assert_length!(delays, tr_nr, DELAY);
 
+     let delay_multipliers = match obj.get(DELAY_MULT) {
+         None => vec![0.0; tr_nr],
+         Some(json) => {
+             let multiplier_jsons = json.as_array()
+                 .ok_or_else(|| WrongJsonValue(DELAY_MULT.to_string(), "expected array".to_string()))?;
+             mine_scale(multiplier_jsons, DELAY_MULT)?
+         }
+     };
+     assert_length!(delay_multipliers, tr_nr, DELAY_MULT);
+
+     let priorities = match obj.get(PRIORITIES) {
+         None => vec![0; tr_nr],
+         Some(json) => {
+             let priority_jsons = json.as_array()
+                 .ok_or_else(|| WrongJsonValue(PRIORITIES.to_string(), "expected array".to_string()))?;
+             mine_delays(priority_jsons, PRIORITIES)?
+         }
+     };
+     assert_length!(priorities, tr_nr, PRIORITIES);
+
+     let merge_strategies = mine_merge_strategies(obj, pl_nr)?;
+
      let table_jsons  = mine!(obj, as_array, TBL_TRS);
      let mut tables = mine_tables(table_jsons)?;
      assert_length!(tables, tr_nr, TBL_TRS);
 
+     let weights = mine_weights(obj)?;
 
+     let pl_names = mine_optional_strings(obj, PL_NAMES, pl_nr)?;
+     let tr_names = mine_optional_strings(obj, TR_NAMES, tr_nr)?;
+     let pl_descs = mine_optional_strings(obj, PL_DESCS, pl_nr)?;
+     let tr_descs = mine_optional_strings(obj, TR_DESCS, tr_nr)?;
 
      let mut bld = UnifiedPetriNetBuilder::new();
 
@@ -125,7 +206,15 @@ pub fn deseralize(what :&str)-> Result<UnifiedPetriNetBuilder>  {
          if out_tr[tr_id] {
              bld.add_out_transition(extact_from_vec(&mut tables, tr_id));
          } else {
-             bld.add_transition(delays[tr_id] as i32, extact_from_vec(&mut tables, tr_id));
+             let tr = bld.add_transition(delays[tr_id] as i32, extact_from_vec(&mut tables, tr_id));
+             bld.set_delay_multiplier(tr, delay_multipliers[tr_id] as f32);
+         }
+         bld.set_priority(tr_id, priorities[tr_id] as i32);
+         if let Some(ref name) = tr_names[tr_id] {
+             bld.set_trans_name(tr_id, name);
+         }
+         if let Some(ref description) = tr_descs[tr_id] {
+             bld.set_trans_description(tr_id, description);
          }
      }
 
@@ -136,6 +225,13 @@ pub fn deseralize(what :&str)-> Result<UnifiedPetriNetBuilder>  {
              bld.add_place(scales[pl_id] as f32);
          }
          bld.set_innitial_marking(pl_id, extract_from_token_map(&mut init_marking, pl_id));
+         bld.set_merge_strategy(pl_id, merge_strategies[pl_id]);
+         if let Some(ref name) = pl_names[pl_id] {
+             bld.set_place_name(pl_id, name);
+         }
+         if let Some(ref description) = pl_descs[pl_id] {
+             bld.set_place_description(pl_id, description);
+         }
      }
 
      for tr_id in 0..tr_to_pl.len() {
@@ -146,13 +242,126 @@ pub fn deseralize(what :&str)-> Result<UnifiedPetriNetBuilder>  {
 
      for tr_id in 0..pl_needed.len() {
          for pl_id in &pl_needed[tr_id]{
-             bld.connect_place_with_transition(*pl_id, tr_id);
+             let weight = *weights.get(&(*pl_id, tr_id)).unwrap_or(&1.0);
+             bld.connect_place_with_transition_with_weight(*pl_id, tr_id, weight);
          }
      }
 
+     let mut problems = match obj.get(PL_TO_TR) {
+         None => vec![],
+         Some(json) => {
+             let pl_to_tr_jsons = json.as_array()
+                 .ok_or_else(|| WrongJsonValue(PL_TO_TR.to_string(), "expected array".to_string()))?;
+             let pl_to_tr = mine_arcs(pl_to_tr_jsons, tr_nr, PL_TO_TR)?;
+             assert_length!(pl_to_tr, pl_nr, PL_TO_TR);
+             check_place_arcs(&pl_to_tr, &pl_needed)
+         }
+     };
+     problems.extend(bld.validate());
+     if !problems.is_empty() {
+         let messages = problems.iter().map(|p| bld.describe_problem(p)).collect();
+         return Err(InvalidNet(problems, messages));
+     }
+
      Ok(bld)
 }
 
+/// `fromPlaceToTrans` is redundant, FuzzP writes it next to `placesNeededForTrans`
+fn check_place_arcs(pl_to_tr: &Vec<Vec<usize>>, pl_needed: &Vec<Vec<usize>>) -> Vec<StructuralProblem> {
+    let mut problems = vec![];
+    for (pl_id, listed) in pl_to_tr.iter().enumerate() {
+        let needed : Vec<usize> = (0..pl_needed.len())
+            .filter(|tr_id| pl_needed[*tr_id].contains(&pl_id))
+            .collect();
+        let mut sorted_listed = listed.clone();
+        sorted_listed.sort();
+        sorted_listed.dedup();
+        if sorted_listed != needed {
+            problems.push(StructuralProblem::PlaceArcsDisagree{
+                pl_id: pl_id, listed: listed.clone(), needed: needed});
+        }
+    }
+    problems
+}
+
+/// Reads the optional `weights` object, keyed as place -> transition -> weight.
+pub fn mine_weights(obj :&BTreeMap<String, Json>) -> Result<HashMap<(usize, usize), f32>> {
+    let mut to_ret = HashMap::new();
+    let weights_obj = match obj.get(WEIGHTS) {
+        None => return Ok(to_ret),
+        Some(json) => json.as_object()
+            .ok_or_else(|| WrongJsonValue(WEIGHTS.to_string(), "expected object".to_string()))?,
+    };
+    for (pl_str, trans_json) in weights_obj {
+        let pl_path = key_path(WEIGHTS, pl_str);
+        let pl_id = pl_str.parse::<usize>()
+            .map_err(|_| WrongJsonValue(pl_path.clone(), format!("place id expected, found \"{}\"", pl_str)))?;
+        let trans_obj = trans_json.as_object()
+            .ok_or_else(|| WrongJsonValue(pl_path.clone(), "expected object".to_string()))?;
+        for (tr_str, weight_json) in trans_obj {
+            let tr_path = key_path(&pl_path, tr_str);
+            let tr_id = tr_str.parse::<usize>()
+                .map_err(|_| WrongJsonValue(tr_path.clone(), format!("transition id expected, found \"{}\"", tr_str)))?;
+            let weight = weight_json.as_f64()
+                .ok_or_else(|| WrongJsonValue(tr_path.clone(), "expected f64".to_string()))?;
+            to_ret.insert((pl_id, tr_id), weight as f32);
+        }
+    }
+    Ok(to_ret)
+}
+
+/// Reads an optional array of strings, `null` elements and a missing key both mean no value
+pub fn mine_optional_strings(obj :&BTreeMap<String, Json>, key: &'static str, len: usize)
+    -> Result<Vec<Option<String>>> {
+    let jsons = match obj.get(key) {
+        None => return Ok(vec![None; len]),
+        Some(json) => json.as_array()
+            .ok_or_else(|| WrongJsonValue(key.to_string(), "expected array".to_string()))?,
+    };
+    let mut to_ret = Vec::new();
+    for (i, json) in jsons.iter().enumerate() {
+        match *json {
+            Json::Null => to_ret.push(None),
+            Json::String(ref s) => to_ret.push(Some(s.clone())),
+            _ => return Err(WrongJsonValue(index_path(key, i), "expected string".to_string())),
+        }
+    }
+    assert_length!(to_ret, len, key);
+    Ok(to_ret)
+}
+
+/// A missing key leaves every place with the default strategy
+fn mine_merge_strategies(obj :&BTreeMap<String, Json>, pl_nr: usize) -> Result<Vec<MergeStrategy>> {
+    let jsons = match obj.get(MERGE) {
+        None => return Ok(vec![MergeStrategy::default(); pl_nr]),
+        Some(json) => json.as_array()
+            .ok_or_else(|| WrongJsonValue(MERGE.to_string(), "expected array".to_string()))?,
+    };
+    let mut to_ret = Vec::new();
+    for (i, json) in jsons.iter().enumerate() {
+        let path = index_path(MERGE, i);
+        let name = json.as_string()
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected string".to_string()))?;
+        to_ret.push(mine_merge_strategy(name, &path)?);
+    }
+    assert_length!(to_ret, pl_nr, MERGE);
+    Ok(to_ret)
+}
+
+fn mine_merge_strategy(what : &str, path: &str) -> Result<MergeStrategy> {
+     let rez = match what {
+         "unite" =>  MergeStrategy::Unite,
+         "average" =>  MergeStrategy::Average,
+         "overwrite" =>  MergeStrategy::Overwrite,
+         "keepFirst" =>  MergeStrategy::KeepFirst,
+         "max" =>  MergeStrategy::Max,
+         "min" =>  MergeStrategy::Min,
+         "sum" =>  MergeStrategy::Sum,
+           _  => {return Err(WrongJsonValue(path.to_string(), format!("unknown merge strategy \"{}\"", what)))},
+     };
+     Ok(rez)
+}
+
 fn extact_from_vec(vec :&mut Vec<UnifiedTableE>, tr_id:usize) -> UnifiedTableE {
     let replace_with = UnifiedTableE::oxo(UnifiedOneXOneTable::default_table());
     mem::replace(&mut vec[tr_id], replace_with)
@@ -165,127 +374,178 @@ fn extract_from_token_map(vec :&mut Vec<UnifiedToken>, pl_id: usize ) -> Unified
 
 fn mine_tables(table_jsons: &Vec<Json>) ->Result<Vec<UnifiedTableE>> {
     let mut to_ret = Vec::new();
-    for table_json in table_jsons {
-        let table_obj = table_json.as_object().ok_or(WrongJsonValue(TBL_TRS))?;
-        let table_type = table_obj
-            .get(TBL_TYPE).ok_or(WrongJsonValue(TBL_TYPE))?
-            .as_string().ok_or(WrongJsonValue(TBL_TYPE))?;
-        let table_data_obj = table_obj
-            .get(TBL_DATA).ok_or(WrongJsonValue(TBL_DATA))?
-            .as_object().ok_or(WrongJsonValue(TBL_DATA))?;
+    for (tr_id, table_json) in table_jsons.iter().enumerate() {
+        let path = &index_path(TBL_TRS, tr_id);
+        let table_obj = table_json.as_object()
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected object".to_string()))?;
+        let table_type = mine!(table_obj, as_string, TBL_TYPE, path);
+        let table_data_obj = mine!(table_obj, as_object, TBL_DATA, path);
+        let data_path = &key_path(path, TBL_DATA);
         let table = match table_type.as_ref() {
-            "u1x1" => mine_oxo(table_data_obj),
-            "u2x1" => mine_txo(table_data_obj),
-            "u1x2" => mine_oxt(table_data_obj),
-            "u2x2" => mine_txt(table_data_obj),
-            _     => {return Err(WrongJsonValue(TBL_TYPE))}
+            "u1x1" => mine_oxo(table_data_obj, data_path),
+            "u2x1" => mine_txo(table_data_obj, data_path),
+            "u1x2" => mine_oxt(table_data_obj, data_path),
+            "u2x2" => mine_txt(table_data_obj, data_path),
+            "uNxM" => mine_nxm(table_data_obj, data_path),
+            _     => {return Err(WrongJsonValue(key_path(path, TBL_TYPE),
+                                                format!("unknown table type \"{}\"", table_type)))}
         };
         to_ret.push(table?);
     }
     Ok(to_ret)
 }
-static VAL_TBL: &'static str = "valTable";
-
-fn mine_oxo(data :&BTreeMap<String, Json>) -> Result<UnifiedTableE> {
-    let full_table = mine!(data, as_object, TABEL);
-    let single_table = mine!(full_table, as_object, VAL_TBL);
-    let mut table_arr = [TableValue::Phi; 6];
-    for (key, val) in single_table {
-        let value_str = val.as_string().ok_or(WrongJsonValue("fuzzy value"))?;
-        let key_v = mine_table_val(key)?;
-        let val_v = mine_table_val(value_str)?;
-        table_arr[key_v.index()] = val_v;
-    }
+pub static VAL_TBL: &'static str = "valTable";
 
-    Ok(UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr(table_arr)))
+fn mine_oxo(data :&BTreeMap<String, Json>, path: &str) -> Result<UnifiedTableE> {
+    let full_table = mine!(data, as_object, TABEL, path);
+    let tbl = mine_oxo_arr(full_table, &key_path(path, TABEL))?;
+    Ok(UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr(tbl)))
 }
 
-static RULE_TBL: &'static str = "ruleTable";
+pub fn mine_oxo_arr(full_table :&BTreeMap<String, Json>, path: &str) -> Result<[TableValue; 6]> {
+    let single_table = mine!(full_table, as_object, VAL_TBL, path);
+    let mut table_arr = [TableValue::Phi; 6];
+    mine_val_table(single_table, &key_path(path, VAL_TBL), 1, 0, &mut table_arr)?;
+    Ok(table_arr)
+}
 
-fn mine_txo(data :&BTreeMap<String, Json>) -> Result<UnifiedTableE> {
-    let full_table = mine!(data, as_object, TABEL);
-    let single_table = mine!(full_table, as_object, RULE_TBL);
-     let mut tbl = [TableValue::Phi; 36];
-     for (big_index_str, value) in single_table {
-        let big_index = mine_table_val(&big_index_str)?.index();
-        let small_tbl = value.as_object().ok_or(WrongJsonValue("fuzzy value"))?;
-        for (small_index_str, final_val_json) in small_tbl {
-            let small_index = mine_table_val(&small_index_str)?.index();
-            let final_fv = mine_table_val(final_val_json.as_string()
-                                          .ok_or(WrongJsonValue("fuzzy value") )?)?;
-            tbl[big_index*6 + small_index] = final_fv;
-        }
-     }
+pub static RULE_TBL: &'static str = "ruleTable";
 
-    let op_str = mine!(data, as_string, OPERATOR);
-    let op = mine_operator(&op_str)?;
+fn mine_txo(data :&BTreeMap<String, Json>, path: &str) -> Result<UnifiedTableE> {
+    let full_table = mine!(data, as_object, TABEL, path);
+    let tbl = mine_txo_arr(full_table, &key_path(path, TABEL))?;
+    let op_str = mine!(data, as_string, OPERATOR, path);
+    let op = mine_operator(&op_str, &key_path(path, OPERATOR))?;
     Ok(UnifiedTableE::txo(UnifiedTwoXOneTable::from_arr(tbl, op)))
 }
 
-static VAL_TBL1: &'static str = "valTable1";
-static VAL_TBL2: &'static str = "valTable2";
+pub fn mine_txo_arr(full_table :&BTreeMap<String, Json>, path: &str) -> Result<[TableValue; 36]> {
+    let single_table = mine!(full_table, as_object, RULE_TBL, path);
+    let mut tbl = [TableValue::Phi; 36];
+    mine_rule_table(single_table, &key_path(path, RULE_TBL), 1, 0, &mut tbl)?;
+    Ok(tbl)
+}
 
-fn mine_oxt(json :&BTreeMap<String, Json>) -> Result<UnifiedTableE>{
-     let data = mine!(json, as_object, TABEL);
-     let value_table1 = mine!(data, as_object, VAL_TBL1);
-     let value_table2 = mine!(data, as_object, VAL_TBL2);
-     let mut table_arr = [TableValue::Phi; 12];
+pub static VAL_TBL1: &'static str = "valTable1";
+pub static VAL_TBL2: &'static str = "valTable2";
 
-     for (key, value) in value_table1 {
-         let value_str = value.as_string().ok_or(WrongJsonValue("fuzzy value"))?;
-         let key_table_value = mine_table_val(&key)?;
-         let value_table_value = mine_table_val(&value_str)?;
-         table_arr[key_table_value.index()*2] = value_table_value;
-     }
+fn mine_oxt(json :&BTreeMap<String, Json>, path: &str) -> Result<UnifiedTableE>{
+     let data = mine!(json, as_object, TABEL, path);
+     let tbl = mine_oxt_arr(data, &key_path(path, TABEL))?;
+     Ok(UnifiedTableE::oxt(UnifiedOneXTwoTable::from_arr(tbl)))
+}
 
-     for (key, value) in value_table2 {
-         let value_str = value.as_string().ok_or(WrongJsonValue("fuzzy value"))?;
-         let key_table_value = mine_table_val(&key)?;
-         let value_table_value = mine_table_val(&value_str)?;
-         table_arr[key_table_value.index()*2 +1 ] = value_table_value;
-     }
+pub fn mine_oxt_arr(data :&BTreeMap<String, Json>, path: &str) -> Result<[TableValue; 12]>{
+     let value_table1 = mine!(data, as_object, VAL_TBL1, path);
+     let value_table2 = mine!(data, as_object, VAL_TBL2, path);
+     let mut table_arr = [TableValue::Phi; 12];
+     mine_val_table(value_table1, &key_path(path, VAL_TBL1), 2, 0, &mut table_arr)?;
+     mine_val_table(value_table2, &key_path(path, VAL_TBL2), 2, 1, &mut table_arr)?;
+     Ok(table_arr)
+}
 
-    Ok(UnifiedTableE::oxt(UnifiedOneXTwoTable::from_arr(table_arr)))
+pub static RULE_TBL_ONE: &'static str = "ruleTable1";
+pub static RULE_TBL_TWO: &'static str = "ruleTable2";
+
+fn mine_txt(data :&BTreeMap<String, Json>, path: &str) -> Result<UnifiedTableE>{
+    let full_table = mine!(data, as_object, TABEL, path);
+    let tbl = mine_txt_arr(full_table, &key_path(path, TABEL))?;
+    let op_str = mine!(data, as_string, OPERATOR, path);
+    let op = mine_operator(&op_str, &key_path(path, OPERATOR))?;
+    Ok(UnifiedTableE::txt(UnifiedTwoXTwoTable::from_arr(tbl,op)))
 }
 
-static RULE_TBL_ONE: &'static str = "ruleTable1";
-static RULE_TBL_TWO: &'static str = "ruleTable2";
+pub fn mine_txt_arr(full_table :&BTreeMap<String, Json>, path: &str) -> Result<[TableValue; 72]>{
+    let first_table = mine!(full_table, as_object, RULE_TBL_ONE, path);
+    let second_table = mine!(full_table, as_object, RULE_TBL_TWO, path);
+    let mut tbl = [TableValue::Phi; 72];
+    mine_rule_table(first_table, &key_path(path, RULE_TBL_ONE), 2, 0, &mut tbl)?;
+    mine_rule_table(second_table, &key_path(path, RULE_TBL_TWO), 2, 1, &mut tbl)?;
+    Ok(tbl)
+}
 
-fn mine_txt(data :&BTreeMap<String, Json>) -> Result<UnifiedTableE>{
-    let full_table = mine!(data, as_object, TABEL);
-    let first_table = mine!(full_table, as_object, RULE_TBL_ONE);
-    let second_table = mine!(full_table, as_object, RULE_TBL_TWO);
+pub static INPUT_NR: &'static str = "inputNr";
+pub static OUTPUT_NR: &'static str = "outputNr";
+/// one rule table per output, nested one level for every input
+pub static RULE_TBLS: &'static str = "ruleTables";
 
-     let mut tbl = [TableValue::Phi; 72];
+fn mine_nxm(data :&BTreeMap<String, Json>, path: &str) -> Result<UnifiedTableE> {
+    let inputs = mine!(data, as_u64, INPUT_NR, path) as usize;
+    if inputs == 0 || inputs > MAX_NXM_INPUTS {
+        return Err(WrongJsonValue(key_path(path, INPUT_NR), format!("expected 1 to {} inputs", MAX_NXM_INPUTS)))
+    }
+    let outputs = mine!(data, as_u64, OUTPUT_NR, path) as usize;
+    if outputs == 0 {
+        return Err(WrongJsonValue(key_path(path, OUTPUT_NR), "expected at least one output".to_string()))
+    }
+    let full_table = mine!(data, as_object, TABEL, path);
+    let table_path = key_path(path, TABEL);
+    let rule_tables = mine!(full_table, as_array, RULE_TBLS, &table_path);
+    let tables_path = key_path(&table_path, RULE_TBLS);
+    assert_length!(rule_tables, outputs, tables_path);
+    let mut tbl = vec![TableValue::Phi; NXMTable::cell_nr(inputs) * outputs];
+    for (out, rule_table) in rule_tables.iter().enumerate() {
+        let out_path = index_path(&tables_path, out);
+        let single_table = rule_table.as_object()
+            .ok_or_else(|| WrongJsonValue(out_path.clone(), "expected object".to_string()))?;
+        mine_nested_rule_table(single_table, &out_path, inputs, 0, outputs, out, &mut tbl)?;
+    }
+    let op_str = mine!(data, as_string, OPERATOR, path);
+    let op = mine_operator(&op_str, &key_path(path, OPERATOR))?;
+    Ok(UnifiedTableE::nxm(UnifiedNXMTable::from_vec(inputs, outputs, tbl, op)))
+}
 
-     for (big_index_str, value) in first_table {
-        let big_index = mine_table_val(&big_index_str)?.index();
-        let small_tbl = value.as_object().ok_or(WrongJsonValue("fuzzy value"))?;
-        for (small_index_str, final_val_json) in small_tbl {
-            let small_index = mine_table_val(&small_index_str)?.index();
-            let final_fv = mine_table_val(final_val_json.as_string()
-                                          .ok_or(WrongJsonValue("fuzzy value") )?)?;
-            tbl[(big_index*6 + small_index) * 2] = final_fv;
+/// `depth` is the number of inputs left to index, `cell` the cell of the ones already indexed
+fn mine_nested_rule_table(single_table :&BTreeMap<String, Json>, path: &str, depth: usize, cell: usize,
+                          outs: usize, out: usize, tbl: &mut [TableValue]) -> Result<()> {
+    for (key, value) in single_table {
+        let val_path = key_path(path, key);
+        let next_cell = cell*6 + mine_table_val(key, &val_path)?.index();
+        if depth == 1 {
+            let value_str = value.as_string()
+                .ok_or_else(|| WrongJsonValue(val_path.clone(), "expected string".to_string()))?;
+            tbl[next_cell*outs + out] = mine_table_val(value_str, &val_path)?;
+        } else {
+            let inner = value.as_object()
+                .ok_or_else(|| WrongJsonValue(val_path.clone(), "expected object".to_string()))?;
+            mine_nested_rule_table(inner, &val_path, depth - 1, next_cell, outs, out, tbl)?;
         }
-     }
+    }
+    Ok(())
+}
+
+/// `outs` is the number of interleaved outputs in `tbl`, `out` the one filled from `single_table`
+fn mine_val_table(single_table :&BTreeMap<String, Json>, path: &str,
+                  outs: usize, out: usize, tbl: &mut [TableValue]) -> Result<()> {
+    for (key, value) in single_table {
+        let val_path = key_path(path, key);
+        let key_v = mine_table_val(key, &val_path)?;
+        let value_str = value.as_string()
+            .ok_or_else(|| WrongJsonValue(val_path.clone(), "expected string".to_string()))?;
+        tbl[key_v.index()*outs + out] = mine_table_val(value_str, &val_path)?;
+    }
+    Ok(())
+}
 
-     for (big_index_str, value) in second_table {
-        let big_index = mine_table_val(&big_index_str)?.index();
-        let small_tbl = value.as_object().ok_or(WrongJsonValue("fuzzy value"))?;
+fn mine_rule_table(single_table :&BTreeMap<String, Json>, path: &str,
+                   outs: usize, out: usize, tbl: &mut [TableValue]) -> Result<()> {
+     for (big_index_str, value) in single_table {
+        let big_path = key_path(path, big_index_str);
+        let big_index = mine_table_val(&big_index_str, &big_path)?.index();
+        let small_tbl = value.as_object()
+            .ok_or_else(|| WrongJsonValue(big_path.clone(), "expected object".to_string()))?;
         for (small_index_str, final_val_json) in small_tbl {
-            let small_index = mine_table_val(&small_index_str)?.index();
-            let final_fv = mine_table_val(final_val_json.as_string()
-                                          .ok_or(WrongJsonValue("fuzzy value") )?)?;
-            tbl[(big_index*6 + small_index) * 2 +1] = final_fv;
+            let small_path = key_path(&big_path, small_index_str);
+            let small_index = mine_table_val(&small_index_str, &small_path)?.index();
+            let final_str = final_val_json.as_string()
+                .ok_or_else(|| WrongJsonValue(small_path.clone(), "expected string".to_string()))?;
+            tbl[(big_index*6 + small_index) * outs + out] = mine_table_val(final_str, &small_path)?;
         }
      }
-
-    let op_str = mine!(data, as_string, OPERATOR);
-    let op = mine_operator(&op_str)?;
-    Ok(UnifiedTableE::txt(UnifiedTwoXTwoTable::from_arr(tbl,op)))
+     Ok(())
 }
 
-fn mine_table_val(what : &str) -> Result<TableValue> {
+fn mine_table_val(what : &str, path: &str) -> Result<TableValue> {
      let rez = match what {
          "NL" =>  TableValue::E(FuzzyValue::NL),
          "NM" =>  TableValue::E(FuzzyValue::NM),
@@ -293,46 +553,54 @@ fn mine_table_val(what : &str) -> Result<TableValue> {
          "PM" =>  TableValue::E(FuzzyValue::PM),
          "PL" =>  TableValue::E(FuzzyValue::PL),
          "FF" =>  TableValue::Phi,
-           _  => {return Err(WrongJsonValue("fuzzy value"))},
+           _  => {return Err(WrongJsonValue(path.to_string(), format!("unknown fuzzy value \"{}\"", what)))},
      };
      Ok(rez)
  }
 
-fn mine_operator(what : &str) -> Result<Operator> {
+fn mine_operator(what : &str, path: &str) -> Result<Operator> {
      let rez = match what {
          "None" =>  Operator::NoOp,
          "PLUS" =>  Operator::Plus,
          "MINUS" =>  Operator::Minus,
          "MULT" =>  Operator::Mult,
          "DIV" =>  Operator::Div,
-           _  => {return Err(WrongJsonValue("fuzzy value"))},
+           _  => {return Err(WrongJsonValue(path.to_string(), format!("unknown operator \"{}\"", what)))},
      };
      Ok(rez)
  }
 
 
-fn mine_delays(scales: &Vec<Json>, talking_about: &'static str) -> Result<Vec<i64>> {
-    scales.iter()
-        .map(|inner_json| inner_json.as_i64().ok_or(WrongJsonValue(talking_about)))
+pub fn mine_delays(scales: &Vec<Json>, talking_about: &'static str) -> Result<Vec<i64>> {
+    scales.iter().enumerate()
+        .map(|(i, inner_json)| inner_json.as_i64()
+             .ok_or_else(|| WrongJsonValue(index_path(talking_about, i), "expected i64".to_string())))
         .collect()
 }
 
 fn mine_scale(scales: &Vec<Json>, talking_about: &'static str) -> Result<Vec<f64>> {
-    scales.iter()
-        .map(|inner_json| inner_json.as_f64().ok_or(WrongJsonValue(talking_about)))
+    scales.iter().enumerate()
+        .map(|(i, inner_json)| inner_json.as_f64()
+             .ok_or_else(|| WrongJsonValue(index_path(talking_about, i), "expected f64".to_string())))
         .collect()
 }
 
 
-fn mine_arcs( arc_jsons: &Vec<Json>,  talking_about: &'static str ) -> Result<Vec<Vec<usize>>> {
+/// `max_id` is the number of nodes the arcs can point to
+pub fn mine_arcs( arc_jsons: &Vec<Json>, max_id: usize, talking_about: &'static str ) -> Result<Vec<Vec<usize>>> {
     let mut to_ret = Vec::new();
-    for inner_json in arc_jsons {
+    for (i, inner_json) in arc_jsons.iter().enumerate() {
+        let path = index_path(talking_about, i);
         let unmined = inner_json.as_array()
-            .ok_or(WrongJsonValue(talking_about))?;
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected array".to_string()))?;
         let mut to_push = Vec::new();
-        for one_val_json in unmined {
+        for (j, one_val_json) in unmined.iter().enumerate() {
             let one_val = one_val_json.as_u64()
-                .ok_or(WrongJsonValue(talking_about))?;
+                .ok_or_else(|| WrongJsonValue(index_path(&path, j), "expected u64".to_string()))?;
+            if one_val as usize >= max_id {
+                return Err(WrongJsonValue(index_path(&path, j),
+                                          format!("id {} out of range, only {} exist", one_val, max_id)));
+            }
             to_push.push(one_val as usize);
         }
         to_ret.push(to_push);
@@ -344,24 +612,26 @@ fn mine_arcs( arc_jsons: &Vec<Json>,  talking_about: &'static str ) -> Result<Ve
 
 fn mine_init_marking(inital_markings: &Vec<Json>) -> Result<Vec<UnifiedToken>> {
     let mut to_ret = Vec::new();
-    for js_val in inital_markings {
-        let val = js_val.as_object().ok_or(WrongJsonValue(INIT_PL))?;
-        let phi = mine!(val, as_boolean,IS_PHI);
+    for (pl_id, js_val) in inital_markings.iter().enumerate() {
+        let path = &index_path(INIT_PL, pl_id);
+        let val = js_val.as_object()
+            .ok_or_else(|| WrongJsonValue(path.clone(), "expected object".to_string()))?;
+        let phi = mine!(val, as_boolean,IS_PHI, path);
         if phi {
             to_ret.push(UnifiedToken::Phi);
         } else {
-            let token_value = mine!(val, as_f64, TOKNE_VAL);
+            let token_value = mine!(val, as_f64, TOKNE_VAL, path);
             to_ret.push(UnifiedToken::from_val(token_value as f32));
         }
     }
     Ok(to_ret)
 }
 
-fn mine_bool_vec(bools: &Vec<Json>, talking_about: &'static str) -> Result<Vec<bool>> {
+pub fn mine_bool_vec(bools: &Vec<Json>, talking_about: &'static str) -> Result<Vec<bool>> {
     let mut to_ret = Vec::new();
-    for v in bools {
+    for (i, v) in bools.iter().enumerate() {
         let unmined = v.as_boolean()
-            .ok_or(WrongJsonValue(talking_about))?;
+            .ok_or_else(|| WrongJsonValue(index_path(talking_about, i), "expected boolean".to_string()))?;
         to_ret.push(unmined);
     }
     Ok(to_ret)
@@ -375,14 +645,175 @@ mod tests {
     #[test]
     fn max_try_run(){
 
-        let ww = my_file_read("unified_nets/maxTableTryOut.json");
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
         let rez = deseralize(&ww);
         assert!(rez.is_ok());
 
         let bld = rez.unwrap();
-        let (net, _) = bld.build();
+        let (net, _) = bld.build().unwrap();
         assert!(net.get_place_nr()==3);
         assert!(net.get_trans_nr()==2);
+        assert_eq!(net.get_weights_befor_trans(1), &vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn delay_multiplier_is_read(){
+        let ww = my_file_read("unified_nets/blink.json").unwrap();
+        let changed = ww.replacen("\"delayMulitiperForTransition\": [\n    0.0,\n    0.0,\n    0.0",
+                                  "\"delayMulitiperForTransition\": [\n    0.0,\n    0.0,\n    2.5", 1);
+        assert!(changed != ww);
+        let (net, _) = deseralize(&changed).unwrap().build().unwrap();
+        assert_eq!(net.get_delay_multiplier(2), 2.5);
+        assert_eq!(net.get_delay_multiplier(1), 0.0);
+
+        let wrong_length = ww.replacen("\"delayMulitiperForTransition\": [\n    0.0,",
+                                       "\"delayMulitiperForTransition\": [", 1);
+        assert!(deseralize(&wrong_length).is_err());
+    }
+
+    #[test]
+    fn priorities_are_read(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let (net, _) = deseralize(&ww).unwrap().build().unwrap();
+        assert_eq!(net.get_priority(1), 0);
+
+        let with_priorities = ww.replacen("{", "{\"priorityForTransition\": [0, 7],", 1);
+        let (net, _) = deseralize(&with_priorities).unwrap().build().unwrap();
+        assert_eq!(net.get_priority(1), 7);
+
+        let bad_priorities = ww.replacen("{", "{\"priorityForTransition\": [0, 0.5],", 1);
+        assert_eq!(format!("{}", deseralize(&bad_priorities).err().unwrap()),
+                   "priorityForTransition[1]: expected i64");
+    }
+
+    #[test]
+    fn merge_strategies_are_read(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let (net, _) = deseralize(&ww).unwrap().build().unwrap();
+        assert_eq!(net.get_merge_strategy(0), MergeStrategy::Unite);
+
+        let with_merge = ww.replacen("{", "{\"mergeStrategyForPlace\": [\"average\", \"max\", \"keepFirst\"],", 1);
+        let (net, _) = deseralize(&with_merge).unwrap().build().unwrap();
+        assert_eq!(net.get_merge_strategy(0), MergeStrategy::Average);
+        assert_eq!(net.get_merge_strategy(2), MergeStrategy::KeepFirst);
+
+        let bad_merge = ww.replacen("{", "{\"mergeStrategyForPlace\": [\"unite\", \"mean\", \"sum\"],", 1);
+        assert_eq!(format!("{}", deseralize(&bad_merge).err().unwrap()),
+                   "mergeStrategyForPlace[1]: unknown merge strategy \"mean\"");
+    }
+
+    #[test]
+    fn weights_are_read(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let with_weights = ww.replacen("{", "{\"weights\": {\"1\": {\"1\": 0.5}},", 1);
+        let (net, _) = deseralize(&with_weights).unwrap().build().unwrap();
+        assert_eq!(net.get_weights_befor_trans(1), &vec![1.0, 0.5]);
+        assert_eq!(net.get_weights_befor_trans(0), &vec![1.0]);
+
+        let bad_weights = ww.replacen("{", "{\"weights\": {\"x\": {\"1\": 0.5}},", 1);
+        assert_eq!(format!("{}", deseralize(&bad_weights).err().unwrap()),
+                   "weights.x: place id expected, found \"x\"");
+    }
+
+    #[test]
+    fn errors_have_json_path(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let bld = deseralize(&ww).unwrap();
+        let (net, _) = bld.build().unwrap();
+        let tr_id = (0..net.get_trans_nr())
+            .find(|tr| match *net.typed_table_for_trans(*tr) { UnifiedTableE::txo(_) => true, _ => false })
+            .unwrap();
+        let mut obj = parse_root(&ww).unwrap();
+        {
+            let tables = obj.get_mut(TBL_TRS).unwrap();
+            if let Json::Array(ref mut tables) = *tables {
+                if let Json::Object(ref mut tbl) = tables[tr_id] {
+                    if let Some(&mut Json::Object(ref mut data)) = tbl.get_mut(TBL_DATA) {
+                        if let Some(&mut Json::Object(ref mut table)) = data.get_mut(TABEL) {
+                            if let Some(&mut Json::Object(ref mut rules)) = table.get_mut(RULE_TBL) {
+                                if let Some(&mut Json::Object(ref mut row)) = rules.get_mut("PM") {
+                                    row.insert("NL".to_string(), Json::String("XX".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let broken = format!("{}", Json::Object(obj));
+        let err = deseralize(&broken).err().unwrap();
+        assert_eq!(format!("{}", err),
+                   format!("tableForTransition[{}].unifiedData.table.ruleTable.PM.NL: unknown fuzzy value \"XX\"", tr_id));
+    }
+
+    #[test]
+    fn malformed_input_is_an_error(){
+        match deseralize("{ not json") {
+            Err(InvalidJson(_)) => {},
+            other => panic!("unexpected {:?}", other.is_ok()),
+        }
+        assert_eq!(deseralize("[1, 2]").err(),
+                   Some(WrongJsonValue("root".to_string(), "expected object".to_string())));
+
+        let ww = my_file_read("unified_nets/blink.json").unwrap();
+        let missing = ww.replacen("\"placeCntr\"", "\"placeCounter\"", 1);
+        assert_eq!(deseralize(&missing).err(), Some(JsonKeyNotFound(PL_NR.to_string())));
+
+        let bad_marking = ww.replacen("\"isPhi\": false", "\"isPhi\": 3", 1);
+        assert!(bad_marking != ww);
+        let err = deseralize(&bad_marking).err().unwrap();
+        assert!(format!("{}", err).starts_with("initialMarkingOfThePlaces["), "{}", err);
+        assert!(format!("{}", err).ends_with("].isPhi: expected boolean"), "{}", err);
+    }
+
+    #[test]
+    fn arc_out_of_range_is_an_error(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let mut obj = parse_root(&ww).unwrap();
+        obj.insert(TR_TO_PL.to_string(), Json::from_str("[[7], [2]]").unwrap());
+        let err = deseralize(&format!("{}", Json::Object(obj))).err().unwrap();
+        assert_eq!(format!("{}", err), "fromTransToPlace[0][0]: id 7 out of range, only 3 exist");
+    }
+
+    #[test]
+    fn structural_problems_are_reported(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let mut obj = parse_root(&ww).unwrap();
+        obj.insert(PLACES_NEED.to_string(), Json::from_str("[[0], [1]]").unwrap());
+        let err = deseralize(&format!("{}", Json::Object(obj))).err().unwrap();
+        match err {
+            InvalidNet(ref problems, _) => {
+                assert!(problems.contains(&StructuralProblem::WrongInputNr{tr_id: 1, expected: 2, found: 1}));
+                assert!(problems.iter().any(|p| match *p {
+                    StructuralProblem::PlaceArcsDisagree{..} => true,
+                    _ => false,
+                }));
+            }
+            _ => panic!("unexpected {}", err),
+        }
+    }
+
+    #[test]
+    fn names_are_read(){
+        let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+        let mut obj = parse_root(&ww).unwrap();
+        obj.insert(PL_NAMES.to_string(), Json::from_str("[\"a\", null, \"c\"]").unwrap());
+        obj.insert(TR_DESCS.to_string(), Json::from_str("[null, \"max of a and b\"]").unwrap());
+        let (net, _) = deseralize(&format!("{}", Json::Object(obj.clone()))).unwrap().build().unwrap();
+        assert_eq!(net.place_by_name("c"), Some(2));
+        assert_eq!(net.get_place_name(1), None);
+        assert_eq!(net.get_trans_description(1), Some("max of a and b"));
+
+        obj.insert(PL_NAMES.to_string(), Json::from_str("[\"a\", \"a\", \"c\"]").unwrap());
+        obj.insert(PLACES_NEED.to_string(), Json::from_str("[[0], [0, 1]]").unwrap());
+        let err = deseralize(&format!("{}", Json::Object(obj))).err().unwrap();
+        assert!(format!("{}", err).contains("more places are named a"), "{}", err);
+        assert!(format!("{}", err).contains("c is listed before [0]"), "{}", err);
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error(){
+        assert!(my_file_read("unified_nets/does_not_exist.json").is_err());
     }
 
 }
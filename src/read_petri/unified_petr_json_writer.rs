@@ -0,0 +1,382 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::collections::BTreeMap;
+
+use basic::*;
+use tables::*;
+use unified_petri_net::{UnifiedPetriNet, UnifiedTableE};
+
+use super::unified_petr_json_reader::*;
+
+extern crate rustc_serialize;
+use self::rustc_serialize::json::{Json, ToJson};
+
+type JsonObj = BTreeMap<String, Json>;
+
+pub fn my_file_write(fname: &str, content: &str) -> io::Result<()> {
+    let path = Path::new(fname);
+    let mut f = File::create(&path)?;
+    f.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Writes the net in the same schema `deseralize` reads, so it can be opened in FuzzP.
+pub fn seralize(net: &UnifiedPetriNet) -> String {
+    let mut obj = JsonObj::new();
+    let tr_nr = net.get_trans_nr();
+    let pl_nr = net.get_place_nr();
+
+    obj.insert(TR_NR.to_string(), tr_nr.to_json());
+    obj.insert(PL_NR.to_string(), pl_nr.to_json());
+
+    let inp_pl : Vec<bool> = (0..pl_nr).map(|pl_id| net.is_place_inp(pl_id)).collect();
+    obj.insert(INP_PL.to_string(), inp_pl.to_json());
+
+    let out_tr : Vec<bool> = (0..tr_nr).map(|tr_id| net.is_trans_out(tr_id)).collect();
+    obj.insert(OUT_TR.to_string(), out_tr.to_json());
+
+    let init_marking : Vec<Json> = (0..pl_nr)
+        .map(|pl_id| write_init_marking(net.get_initial_marking(pl_id)))
+        .collect();
+    obj.insert(INIT_PL.to_string(), Json::Array(init_marking));
+
+    let tr_to_pl : Vec<Vec<usize>> = (0..tr_nr)
+        .map(|tr_id| net.get_places_after_trans(tr_id).clone())
+        .collect();
+    obj.insert(TR_TO_PL.to_string(), tr_to_pl.to_json());
+
+    let pl_to_tr : Vec<Vec<usize>> = (0..pl_nr)
+        .map(|pl_id| net.get_tanss_after_place(pl_id).clone())
+        .collect();
+    obj.insert(PL_TO_TR.to_string(), pl_to_tr.to_json());
+
+    let pl_needed : Vec<Vec<usize>> = (0..tr_nr)
+        .map(|tr_id| net.get_places_befor_trans(tr_id).clone())
+        .collect();
+    obj.insert(PLACES_NEED.to_string(), pl_needed.to_json());
+
+    let scales : Vec<f64> = (0..pl_nr).map(|pl_id| net.get_place_scale(pl_id) as f64).collect();
+    obj.insert(SCALES.to_string(), scales.to_json());
+
+    let delays : Vec<i64> = (0..tr_nr).map(|tr_id| net.get_delay(tr_id) as i64).collect();
+    obj.insert(DELAY.to_string(), delays.to_json());
+
+    let multipliers : Vec<f64> = (0..tr_nr)
+        .map(|tr_id| net.get_delay_multiplier(tr_id) as f64)
+        .collect();
+    obj.insert(DELAY_MULT.to_string(), multipliers.to_json());
+
+    let priorities : Vec<i64> = (0..tr_nr).map(|tr_id| net.get_priority(tr_id) as i64).collect();
+    if priorities.iter().any(|p| *p != 0) {
+        obj.insert(PRIORITIES.to_string(), priorities.to_json());
+    }
+
+    let strategies : Vec<MergeStrategy> = (0..pl_nr).map(|pl_id| net.get_merge_strategy(pl_id)).collect();
+    if strategies.iter().any(|m| *m != MergeStrategy::default()) {
+        obj.insert(MERGE.to_string(), Json::Array(strategies.into_iter().map(write_merge_strategy).collect()));
+    }
+
+    let tables : Vec<Json> = (0..tr_nr)
+        .map(|tr_id| write_table(net.typed_table_for_trans(tr_id)))
+        .collect();
+    obj.insert(TBL_TRS.to_string(), Json::Array(tables));
+
+    if let Some(weights) = write_weights(net) {
+        obj.insert(WEIGHTS.to_string(), weights);
+    }
+
+    write_optional_strings(&mut obj, PL_NAMES, (0..pl_nr).map(|pl_id| net.get_place_name(pl_id)).collect());
+    write_optional_strings(&mut obj, TR_NAMES, (0..tr_nr).map(|tr_id| net.get_trans_name(tr_id)).collect());
+    write_optional_strings(&mut obj, PL_DESCS,
+                           (0..pl_nr).map(|pl_id| net.get_place_description(pl_id)).collect());
+    write_optional_strings(&mut obj, TR_DESCS,
+                           (0..tr_nr).map(|tr_id| net.get_trans_description(tr_id)).collect());
+
+    format!("{}", Json::Object(obj).pretty())
+}
+
+/// Nets without names are written without the key, as FuzzP writes them
+fn write_optional_strings(obj: &mut JsonObj, key: &str, values: Vec<Option<&str>>) {
+    if values.iter().all(|v| v.is_none()) {
+        return;
+    }
+    let jsons = values.into_iter()
+        .map(|v| v.map_or(Json::Null, |s| Json::String(s.to_string())))
+        .collect();
+    obj.insert(key.to_string(), Json::Array(jsons));
+}
+
+fn write_init_marking(token: UnifiedToken) -> Json {
+    let mut obj = JsonObj::new();
+    match token {
+        UnifiedToken::Phi => {
+            obj.insert(TOKNE_VAL.to_string(), Json::Null);
+            obj.insert(IS_PHI.to_string(), Json::Boolean(true));
+        }
+        UnifiedToken::Exist(v) => {
+            obj.insert(TOKNE_VAL.to_string(), (v as f64).to_json());
+            obj.insert(IS_PHI.to_string(), Json::Boolean(false));
+        }
+    }
+    Json::Object(obj)
+}
+
+/// Only nets with a non default weight get a `weights` object, the FuzzP editor does not know it.
+fn write_weights(net: &UnifiedPetriNet) -> Option<Json> {
+    let mut per_place : BTreeMap<usize, JsonObj> = BTreeMap::new();
+    let mut has_weight = false;
+    for tr_id in 0..net.get_trans_nr() {
+        let places = net.get_places_befor_trans(tr_id);
+        let weights = net.get_weights_befor_trans(tr_id);
+        for (pl_id, weight) in places.iter().zip(weights) {
+            has_weight = has_weight || *weight != 1.0;
+            per_place.entry(*pl_id).or_insert(JsonObj::new())
+                .insert(tr_id.to_string(), (*weight as f64).to_json());
+        }
+    }
+    if !has_weight {
+        return None;
+    }
+    let mut obj = JsonObj::new();
+    for (pl_id, trans_obj) in per_place {
+        obj.insert(pl_id.to_string(), Json::Object(trans_obj));
+    }
+    Some(Json::Object(obj))
+}
+
+fn write_table(table: &UnifiedTableE) -> Json {
+    let mut obj = JsonObj::new();
+    let mut data = JsonObj::new();
+    let mut table_obj = JsonObj::new();
+    let table_type = match *table {
+        UnifiedTableE::oxo(ref t) => {
+            table_obj.insert(VAL_TBL.to_string(), write_val_table(t.get_values(), 1, 0));
+            "u1x1"
+        }
+        UnifiedTableE::oxt(ref t) => {
+            table_obj.insert(VAL_TBL1.to_string(), write_val_table(t.get_values(), 2, 0));
+            table_obj.insert(VAL_TBL2.to_string(), write_val_table(t.get_values(), 2, 1));
+            "u1x2"
+        }
+        UnifiedTableE::txo(ref t) => {
+            table_obj.insert(RULE_TBL.to_string(), write_rule_table(t.get_values(), 1, 0));
+            data.insert(OPERATOR.to_string(), write_operator(t.get_op()));
+            "u2x1"
+        }
+        UnifiedTableE::txt(ref t) => {
+            table_obj.insert(RULE_TBL_ONE.to_string(), write_rule_table(t.get_values(), 2, 0));
+            table_obj.insert(RULE_TBL_TWO.to_string(), write_rule_table(t.get_values(), 2, 1));
+            data.insert(OPERATOR.to_string(), write_operator(t.get_op()));
+            "u2x2"
+        }
+        UnifiedTableE::nxm(ref t) => {
+            let rule_tables = (0..t.output_nr())
+                .map(|out| write_nested_rule_table(t.get_values(), t.input_nr(), 0, t.output_nr(), out))
+                .collect();
+            table_obj.insert(RULE_TBLS.to_string(), Json::Array(rule_tables));
+            data.insert(INPUT_NR.to_string(), t.input_nr().to_json());
+            data.insert(OUTPUT_NR.to_string(), t.output_nr().to_json());
+            data.insert(OPERATOR.to_string(), write_operator(t.get_op()));
+            "uNxM"
+        }
+    };
+    data.insert(TABEL.to_string(), Json::Object(table_obj));
+    obj.insert(TBL_TYPE.to_string(), table_type.to_json());
+    obj.insert(TBL_DATA.to_string(), Json::Object(data));
+    Json::Object(obj)
+}
+
+static ALL_TABLE_VALUES : [TableValue; 6] = [
+    TableValue::E(FuzzyValue::NL),
+    TableValue::E(FuzzyValue::NM),
+    TableValue::E(FuzzyValue::ZR),
+    TableValue::E(FuzzyValue::PM),
+    TableValue::E(FuzzyValue::PL),
+    TableValue::Phi,
+];
+
+/// `outs` is the number of interleaved outputs in `values`, `out` the one to write
+fn write_val_table(values: &[TableValue], outs: usize, out: usize) -> Json {
+    let mut obj = JsonObj::new();
+    for key in ALL_TABLE_VALUES.iter() {
+        obj.insert(write_table_val(*key), write_table_val(values[key.index()*outs + out]).to_json());
+    }
+    Json::Object(obj)
+}
+
+fn write_rule_table(values: &[TableValue], outs: usize, out: usize) -> Json {
+    let mut obj = JsonObj::new();
+    for big_key in ALL_TABLE_VALUES.iter() {
+        let mut inner = JsonObj::new();
+        for small_key in ALL_TABLE_VALUES.iter() {
+            let value = values[(big_key.index()*6 + small_key.index())*outs + out];
+            inner.insert(write_table_val(*small_key), write_table_val(value).to_json());
+        }
+        obj.insert(write_table_val(*big_key), Json::Object(inner));
+    }
+    Json::Object(obj)
+}
+
+/// `depth` is the number of inputs left to index, `cell` the cell of the ones already indexed
+fn write_nested_rule_table(values: &[TableValue], depth: usize, cell: usize, outs: usize, out: usize) -> Json {
+    let mut obj = JsonObj::new();
+    for key in ALL_TABLE_VALUES.iter() {
+        let next_cell = cell*6 + key.index();
+        let value = if depth == 1 {
+            write_table_val(values[next_cell*outs + out]).to_json()
+        } else {
+            write_nested_rule_table(values, depth - 1, next_cell, outs, out)
+        };
+        obj.insert(write_table_val(*key), value);
+    }
+    Json::Object(obj)
+}
+
+fn write_table_val(what: TableValue) -> String {
+    let rez = match what {
+        TableValue::E(FuzzyValue::NL) => "NL",
+        TableValue::E(FuzzyValue::NM) => "NM",
+        TableValue::E(FuzzyValue::ZR) => "ZR",
+        TableValue::E(FuzzyValue::PM) => "PM",
+        TableValue::E(FuzzyValue::PL) => "PL",
+        TableValue::Phi => "FF",
+    };
+    rez.to_string()
+}
+
+fn write_operator(what: Operator) -> Json {
+    let rez = match what {
+        Operator::NoOp => "None",
+        Operator::Plus => "PLUS",
+        Operator::Minus => "MINUS",
+        Operator::Mult => "MULT",
+        Operator::Div => "DIV",
+    };
+    rez.to_json()
+}
+
+fn write_merge_strategy(what: MergeStrategy) -> Json {
+    let rez = match what {
+        MergeStrategy::Unite => "unite",
+        MergeStrategy::Average => "average",
+        MergeStrategy::Overwrite => "overwrite",
+        MergeStrategy::KeepFirst => "keepFirst",
+        MergeStrategy::Max => "max",
+        MergeStrategy::Min => "min",
+        MergeStrategy::Sum => "sum",
+    };
+    rez.to_json()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use unified_petri_net::UnifiedPetriNetBuilder;
+
+    fn round_trip(fname: &str) {
+        let ww = my_file_read(fname).unwrap();
+        let (net, _) = deseralize(&ww).unwrap().build().unwrap();
+
+        let written = seralize(&net);
+        let (reread_net, _) = deseralize(&written).unwrap().build().unwrap();
+        assert_eq!(net, reread_net);
+        assert_eq!(written, seralize(&reread_net));
+    }
+
+    #[test]
+    fn blink_round_trip() {
+        round_trip("unified_nets/blink.json");
+    }
+
+    #[test]
+    fn controller_round_trip() {
+        round_trip("unified_nets/controller.json");
+    }
+
+    #[test]
+    fn lane_round_trip() {
+        round_trip("unified_nets/lane.json");
+    }
+
+    #[test]
+    fn max_table_round_trip() {
+        round_trip("unified_nets/maxTableTryOut.json");
+    }
+
+    #[test]
+    fn built_net_round_trip() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(2.0);
+        let p1 = bld.add_place(3.0);
+        bld.set_innitial_marking(p1, UnifiedToken::from_val(1.5));
+        let t0 = bld.add_transition(2, UnifiedTableE::txt(UnifiedTwoXTwoTable::all_pl(Operator::Div)));
+        bld.set_delay_multiplier(t0, 0.5);
+        bld.connect_place_with_transition_with_weight(ip0, t0, 0.25);
+        bld.connect_place_with_transition(p1, t0);
+        let p2 = bld.add_place(1.0);
+        let p3 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p2);
+        bld.connect_transition_with_place(t0, p3);
+        let t1 = bld.add_transition(0, UnifiedTableE::oxt(UnifiedOneXTwoTable::default_table()));
+        bld.connect_place_with_transition(p2, t1);
+        bld.connect_transition_with_place(t1, p1);
+        let p4 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t1, p4);
+        let ot2 = bld.add_out_transition(UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p3, ot2);
+        bld.set_place_name(ip0, "inp");
+        bld.set_trans_name(ot2, "out");
+        bld.set_trans_description(t1, "splits p2");
+        bld.set_priority(t1, 3);
+        bld.set_merge_strategy(ip0, MergeStrategy::Average);
+        let (net, _) = bld.build().unwrap();
+
+        let written = seralize(&net);
+        assert!(written.contains("\"weights\""));
+        assert!(written.contains("\"priorityForTransition\""));
+        assert!(written.contains("\"mergeStrategyForPlace\""));
+        assert!(written.contains("\"placeNames\""));
+        assert!(!written.contains("\"placeDescriptions\""));
+        let (reread_net, _) = deseralize(&written).unwrap().build().unwrap();
+        assert_eq!(net, reread_net);
+    }
+
+
+    #[test]
+    fn nxm_table_round_trip() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let inps : Vec<usize> = (0..3).map(|_| bld.add_inp_place(1.0)).collect();
+        let mut values = vec![TableValue::Phi; 432];
+        values[((2*6 + 5)*6 + 0)*2 + 1] = TableValue::E(FuzzyValue::PM);
+        values[((4*6 + 4)*6 + 4)*2] = TableValue::E(FuzzyValue::NL);
+        let t0 = bld.add_transition(1, UnifiedTableE::nxm(UnifiedNXMTable::from_vec(3, 2, values, Operator::Mult)));
+        for &pl in inps.iter() {
+            bld.connect_place_with_transition(pl, t0);
+        }
+        let p3 = bld.add_place(1.0);
+        let p4 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p3);
+        bld.connect_transition_with_place(t0, p4);
+        let (net, _) = bld.build().unwrap();
+
+        let written = seralize(&net);
+        assert!(written.contains("\"unfiedType\": \"uNxM\""));
+        let (reread_net, _) = deseralize(&written).unwrap().build().unwrap();
+        assert_eq!(net, reread_net);
+        assert_eq!(written, seralize(&reread_net));
+
+        let too_many = written.replacen("\"inputNr\": 3", "\"inputNr\": 9", 1);
+        assert_eq!(format!("{}", deseralize(&too_many).err().unwrap()),
+                   "tableForTransition[0].unifiedData.inputNr: expected 1 to 8 inputs");
+        let too_deep = written.replacen("\"inputNr\": 3", "\"inputNr\": 4", 1);
+        assert_eq!(format!("{}", deseralize(&too_deep).err().unwrap()),
+                   "tableForTransition[0].unifiedData.table.ruleTables[0].FF.FF.FF: expected object");
+        let one_output = written.replacen("\"outputNr\": 2", "\"outputNr\": 1", 1);
+        assert_eq!(format!("{}", deseralize(&one_output).err().unwrap()),
+                   "tableForTransition[0].unifiedData.table.ruleTables: expected 1 elements, found 2");
+    }
+
+}
@@ -78,9 +78,40 @@ pub trait ExecutableUnifiedTable {
     fn is_executable(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> bool;
     fn execute(&self, inps: Vec<UnifiedToken>,fuz: &Vec<&Fuzzyfier>, defuz: &Vec<&Defuzzyfier> ) -> Vec<UnifiedToken>;
     fn possibly_executable(&self, inps: &Vec<bool> ) -> bool;
+    /// How strongly the inputs fire the table, see `rule_activation`
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32;
 }
 
-#[derive(Debug,Clone)]
+/// Strength of the strongest rule with a conclusion the inputs fire,
+/// the product of the input memberships where a phi input counts as 1, 0 if no such rule fires.
+/// `has_conclusion` gets the value index of every input, phi is 5.
+fn rule_activation<F: Fn(&[usize]) -> bool>(inps: &Vec<FuzzyToken>, has_conclusion: F) -> f32 {
+    let mut combos : Vec<(Vec<usize>, f32)> = vec![(vec![], 1.0)];
+    for inp in inps {
+        let choices : Vec<(usize, f32)> = match *inp {
+            FuzzyToken::Phi => vec![(5, 1.0)],
+            FuzzyToken::Exist(_) => inp.nonzero_values().into_iter()
+                .map(|fv| (fv.index(), inp.get_val(*fv)))
+                .collect(),
+        };
+        combos = combos.into_iter()
+            .flat_map(|(idx, strength)| choices.iter().map(move |&(i, m)| {
+                let mut next = idx.clone();
+                next.push(i);
+                (next, strength * m)
+            }))
+            .collect();
+    }
+    combos.into_iter()
+        .filter(|&(ref idx, _)| has_conclusion(idx))
+        .fold(0.0, |best, (_, strength)| if strength > best { strength } else { best })
+}
+
+fn fuzzyfy_all(inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> Vec<FuzzyToken> {
+    inps.iter().zip(fuz).map(|(tk, f)| f.fuzzyfy(tk.as_option())).collect()
+}
+
+#[derive(Debug,Clone, PartialEq)]
 pub struct UnifiedOneXOneTable {
     fuzzy_table: OneXOneTable,
 }
@@ -93,6 +124,10 @@ impl UnifiedOneXOneTable {
     pub fn default_table() -> UnifiedOneXOneTable {
         UnifiedOneXOneTable{fuzzy_table: OneXOneTable::default_table()}
     }
+
+    pub fn get_values(&self) -> &[TableValue; 6] {
+        &self.fuzzy_table.values
+    }
 }
 
 impl ExecutableUnifiedTable for UnifiedOneXOneTable {
@@ -110,9 +145,14 @@ impl ExecutableUnifiedTable for UnifiedOneXOneTable {
     fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
         self.fuzzy_table.possibly_executable(inps)
     }
+
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32 {
+        let vals = &self.fuzzy_table.values;
+        rule_activation(&fuzzyfy_all(inps, fuz), |idx| vals[idx[0]] != Phi)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OneXOneTable {
     values: [TableValue; 6],
 }
@@ -183,7 +223,7 @@ impl ExecutableFuzzyTable for OneXOneTable {
 }
 
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone, PartialEq)]
 pub struct  UnifiedOneXTwoTable {
     fuzzy_table : OneXTwoTable,
 }
@@ -196,6 +236,10 @@ impl UnifiedOneXTwoTable {
     pub fn default_table() -> UnifiedOneXTwoTable {
         UnifiedOneXTwoTable{fuzzy_table: OneXTwoTable::default_table()}
     }
+
+    pub fn get_values(&self) -> &[TableValue; 12] {
+        &self.fuzzy_table.values
+    }
 }
 
 impl ExecutableUnifiedTable for UnifiedOneXTwoTable {
@@ -214,9 +258,14 @@ impl ExecutableUnifiedTable for UnifiedOneXTwoTable {
     fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
         self.fuzzy_table.possibly_executable(inps)
     }
+
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32 {
+        let vals = &self.fuzzy_table.values;
+        rule_activation(&fuzzyfy_all(inps, fuz), |idx| vals[idx[0]*2] != Phi || vals[idx[0]*2 + 1] != Phi)
+    }
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone, PartialEq)]
 pub struct OneXTwoTable {
     values: [TableValue; 12],
 }
@@ -301,7 +350,7 @@ impl ExecutableFuzzyTable for OneXTwoTable {
     }
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone, PartialEq)]
 pub struct UnifiedTwoXOneTable{
     fuzzy_table : TwoXOneTable,
     op : Operator,
@@ -317,6 +366,14 @@ impl UnifiedTwoXOneTable {
         UnifiedTwoXOneTable{fuzzy_table: TwoXOneTable::default_table(), op : Operator::NoOp}
     }
 
+    pub fn get_values(&self) -> &[TableValue; 36] {
+        &self.fuzzy_table.values
+    }
+
+    pub fn get_op(&self) -> Operator {
+        self.op
+    }
+
     pub fn all_pl(op: Operator) -> UnifiedTwoXOneTable {
         let t = TwoXOneTable{ values :
             [E(PL), E(PL), E(PL), E(PL), E(PL),  Phi,
@@ -354,16 +411,22 @@ impl ExecutableUnifiedTable for UnifiedTwoXOneTable {
        } else {
            let defult_driver = TriangleFuzzyfier::with_min_max(-1.0, 1.0);
            let r = op_rez.expect("Impossible") *defult_driver.defuzzyfy(rez).expect("Impossible");
-           vec![UnifiedToken::from_val( defuz[0].limit( r ))]
+           vec![UnifiedToken::Exist(defuz[0].limit(r))]
 
        }
     }
     fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
         self.fuzzy_table.possibly_executable(inps)
     }
+
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32 {
+        let vals = &self.fuzzy_table.values;
+        rule_activation(&fuzzyfy_all(inps, fuz), |idx| vals[idx[0]*6 + idx[1]] != Phi)
+    }
 }
 
 
+#[derive(PartialEq)]
 pub struct TwoXOneTable  {
     values: [TableValue; 36],
 }
@@ -500,7 +563,7 @@ fn index_for(i : bool) -> Vec<TableValue> {
     }
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone, PartialEq)]
 pub struct UnifiedTwoXTwoTable{
     fuzzy_table : TwoXTwoTable,
     op : Operator,
@@ -517,6 +580,14 @@ impl UnifiedTwoXTwoTable {
         UnifiedTwoXTwoTable{fuzzy_table : TwoXTwoTable::default_table(), op: Operator::NoOp}
     }
 
+    pub fn get_values(&self) -> &[TableValue; 72] {
+        &self.fuzzy_table.values
+    }
+
+    pub fn get_op(&self) -> Operator {
+        self.op
+    }
+
     pub fn all_pl(op: Operator) -> UnifiedTwoXTwoTable {
         let t = TwoXTwoTable{ values :
             [E(PL), E(PL), E(PL), E(PL), E(PL), E(PL), E(PL), E(PL), E(PL), E(PL),  Phi,  Phi,
@@ -568,8 +639,14 @@ impl ExecutableUnifiedTable for UnifiedTwoXTwoTable {
     fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
         self.fuzzy_table.possibly_executable(inps)
     }
+
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32 {
+        let vals = &self.fuzzy_table.values;
+        rule_activation(&fuzzyfy_all(inps, fuz), |idx| vals[(idx[0]*6 + idx[1])*2] != Phi || vals[(idx[0]*6 + idx[1])*2 + 1] != Phi)
+    }
 }
 
+#[derive(PartialEq)]
 pub struct TwoXTwoTable  {
     values: [TableValue; 72],
 }
@@ -719,6 +796,192 @@ macro_rules! txt_get_s {
     }
  }
 
+/// Most inputs a `NXMTable` may have, its grid has 6^N cells for every output
+pub const MAX_NXM_INPUTS: usize = 8;
+
+/// Table with any number of inputs and outputs.
+/// The conclusion of output k for the input values (i_0, .., i_N-1), phi being 5,
+/// is at `((i_0*6 + i_1)*6 + ..)*M + k`, the layout of the fixed size tables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NXMTable {
+    inputs: usize,
+    outputs: usize,
+    values: Vec<TableValue>,
+}
+
+impl NXMTable {
+    /// Panics if there are no inputs or outputs, more than `MAX_NXM_INPUTS` inputs,
+    /// or the number of values is not 6^inputs * outputs
+    pub fn from_vec(inputs: usize, outputs: usize, values: Vec<TableValue>) -> NXMTable {
+        assert!(inputs > 0 && inputs <= MAX_NXM_INPUTS, "a table needs 1 to {} inputs", MAX_NXM_INPUTS);
+        assert!(outputs > 0, "a table needs at least one output");
+        assert_eq!(values.len(), NXMTable::cell_nr(inputs) * outputs, "wrong number of table values");
+        NXMTable{inputs: inputs, outputs: outputs, values: values}
+    }
+
+    pub fn cell_nr(inputs: usize) -> usize {
+        6usize.pow(inputs as u32)
+    }
+
+    fn cell_of(idx: &[usize]) -> usize {
+        idx.iter().fold(0, |cell, &i| cell * 6 + i)
+    }
+
+    fn has_conclusion(&self, cell: usize) -> bool {
+        self.values[cell * self.outputs .. (cell + 1) * self.outputs].iter().any(|v| *v != Phi)
+    }
+
+    /// Every cell the inputs fire with its strength, the first input varies slowest
+    fn fired_cells(inps: &Vec<FuzzyToken>) -> Vec<(usize, f32)> {
+        let mut cells = vec![(0, 1.0)];
+        for inp in inps {
+            let choices : Vec<(usize, f32)> = match *inp {
+                FuzzyToken::Phi => vec![(5, 1.0)],
+                FuzzyToken::Exist(_) => inp.nonzero_values().into_iter()
+                    .map(|fv| (fv.index(), inp.get_val(*fv)))
+                    .collect(),
+            };
+            cells = cells.into_iter()
+                .flat_map(|(cell, strength)| choices.iter().map(move |&(i, m)| (cell * 6 + i, strength * m)))
+                .collect();
+        }
+        cells
+    }
+}
+
+impl ExecutableFuzzyTable for NXMTable {
+    fn is_executable(&self, inps: &Vec<FuzzyToken> ) -> bool {
+        NXMTable::fired_cells(inps).into_iter().any(|(cell, _)| self.has_conclusion(cell))
+    }
+
+    fn execute(&self, inps: Vec<FuzzyToken> ) -> Vec<FuzzyToken> {
+        let mut to_ret = vec![FuzzyToken::Phi; self.outputs];
+        for (cell, strength) in NXMTable::fired_cells(&inps) {
+            for (k, out) in to_ret.iter_mut().enumerate() {
+                self.values[cell * self.outputs + k].map_on_value(|conculsion_fv| {
+                    out.add_to_val(conculsion_fv, strength);
+                });
+            }
+        }
+        if inps.iter().any(|inp| *inp != FuzzyToken::Phi) {
+            for out in to_ret.iter_mut() {
+                out.normailze();
+            }
+        }
+        to_ret
+    }
+
+    fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
+        let mut cells = vec![vec![]];
+        for &inp in inps {
+            cells = cells.into_iter()
+                .flat_map(|idx: Vec<usize>| index_for(inp).into_iter().map(move |tv| {
+                    let mut next = idx.clone();
+                    next.push(tv.index());
+                    next
+                }))
+                .collect();
+        }
+        cells.iter().any(|idx| self.has_conclusion(NXMTable::cell_of(idx)))
+    }
+}
+
+/// `NXMTable` for real values, the operator folds the input values from left to right
+/// and scales the outputs like it does for the two input tables
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnifiedNXMTable {
+    fuzzy_table: NXMTable,
+    op: Operator,
+}
+
+impl UnifiedNXMTable {
+    /// Panics on the same shapes as `NXMTable::from_vec`
+    pub fn from_vec(inputs: usize, outputs: usize, values: Vec<TableValue>, op: Operator) -> UnifiedNXMTable {
+        UnifiedNXMTable{fuzzy_table: NXMTable::from_vec(inputs, outputs, values), op: op}
+    }
+
+    pub fn get_values(&self) -> &Vec<TableValue> {
+        &self.fuzzy_table.values
+    }
+
+    pub fn get_op(&self) -> Operator {
+        self.op
+    }
+
+    pub fn input_nr(&self) -> usize {
+        self.fuzzy_table.inputs
+    }
+
+    pub fn output_nr(&self) -> usize {
+        self.fuzzy_table.outputs
+    }
+
+    fn fold_op(&self, inps: &Vec<UnifiedToken>) -> Option<f32> {
+        inps[1..].iter()
+            .fold(inps[0].clone(), |acc, tk| UnifiedToken::from_option(self.op.calc(&acc, tk)))
+            .as_option()
+    }
+}
+
+impl<'a> From<&'a UnifiedOneXOneTable> for UnifiedNXMTable {
+    fn from(t: &UnifiedOneXOneTable) -> UnifiedNXMTable {
+        UnifiedNXMTable::from_vec(1, 1, t.get_values().to_vec(), Operator::NoOp)
+    }
+}
+
+impl<'a> From<&'a UnifiedOneXTwoTable> for UnifiedNXMTable {
+    fn from(t: &UnifiedOneXTwoTable) -> UnifiedNXMTable {
+        UnifiedNXMTable::from_vec(1, 2, t.get_values().to_vec(), Operator::NoOp)
+    }
+}
+
+impl<'a> From<&'a UnifiedTwoXOneTable> for UnifiedNXMTable {
+    fn from(t: &UnifiedTwoXOneTable) -> UnifiedNXMTable {
+        UnifiedNXMTable::from_vec(2, 1, t.get_values().to_vec(), t.get_op())
+    }
+}
+
+impl<'a> From<&'a UnifiedTwoXTwoTable> for UnifiedNXMTable {
+    fn from(t: &UnifiedTwoXTwoTable) -> UnifiedNXMTable {
+        UnifiedNXMTable::from_vec(2, 2, t.get_values().to_vec(), t.get_op())
+    }
+}
+
+impl ExecutableUnifiedTable for UnifiedNXMTable {
+    fn is_executable(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier> ) -> bool {
+        self.fuzzy_table.is_executable(&fuzzyfy_all(inps, fuz))
+    }
+
+    fn execute(&self, inps: Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>, defuz: &Vec<&Defuzzyfier> ) -> Vec<UnifiedToken> {
+        let op_rez = if self.op.exists() {
+            self.fold_op(&inps)
+        } else {
+            None
+        };
+        let fuzzy_out = self.fuzzy_table.execute(fuzzyfy_all(&inps, fuz));
+        match op_rez {
+            None => fuzzy_out.into_iter().zip(defuz)
+                .map(|(rez, d)| UnifiedToken::from_option(d.defuzzyfy(rez)))
+                .collect(),
+            Some(r) => {
+                let defult_driver = TriangleFuzzyfier::with_min_max(-1.0, 1.0);
+                fuzzy_out.into_iter().zip(defuz)
+                    .map(|(rez, d)| UnifiedToken::from_option(defult_driver.defuzzyfy(rez).map(|v| d.limit(r*v))))
+                    .collect()
+            }
+        }
+    }
+
+    fn possibly_executable(&self, inps: &Vec<bool> ) -> bool {
+        self.fuzzy_table.possibly_executable(inps)
+    }
+
+    fn activation(&self, inps: &Vec<UnifiedToken>, fuz: &Vec<&Fuzzyfier>) -> f32 {
+        let table = &self.fuzzy_table;
+        rule_activation(&fuzzyfy_all(inps, fuz), |idx| table.has_conclusion(NXMTable::cell_of(idx)))
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -727,7 +990,8 @@ mod tests {
     use super::{ExecutableFuzzyTable, OneXOneTable,  OneXTwoTable, TwoXOneTable, TwoXTwoTable};
     use super::{ExecutableUnifiedTable, UnifiedOneXOneTable, UnifiedOneXTwoTable};
     use super::{ UnifiedTwoXOneTable, Operator, UnifiedTwoXTwoTable};
-    use basic::{FuzzyToken, UnifiedToken, TriangleFuzzyfier};
+    use super::{NXMTable, UnifiedNXMTable};
+    use basic::{FuzzyToken, UnifiedToken, TriangleFuzzyfier, Fuzzyfier, Defuzzyfier};
     use super::TableValue::*;
     use basic::FuzzyValue::*;
 
@@ -818,6 +1082,20 @@ mod tests {
 
     }
 
+    #[test]
+    fn unified_activation_test(){
+        let fuzz = TriangleFuzzyfier::with_min_max(-1.0,1.0);
+        let table = UnifiedOneXOneTable::from_arr([E(PL), E(PM), E(NL), Phi , E(NL), Phi]);
+        // 0.1 is 0.8 ZR and 0.2 PM, only ZR has a rule
+        assert!((table.activation(&vec![ut!(0.1)], &vec![&fuzz]) - 0.8).abs() < 1e-5);
+        assert!((table.activation(&vec![ut!(0.4)], &vec![&fuzz]) - 0.2).abs() < 1e-5);
+        assert_eq!(table.activation(&vec![UnifiedToken::Phi], &vec![&fuzz]), 0.0);
+
+        let table = UnifiedTwoXOneTable::all_pl(Operator::Plus);
+        assert!((table.activation(&vec![ut!(0.1), ut!(0.4)], &vec![&fuzz, &fuzz]) - 0.64).abs() < 1e-5);
+        assert_eq!(table.activation(&vec![ut!(0.1), UnifiedToken::Phi], &vec![&fuzz, &fuzz]), 0.0);
+    }
+
     #[test]
     fn map_on_value_test() {
         let rule = Phi;
@@ -1275,4 +1553,103 @@ mod tests {
     }
 
 
+
+    fn assert_same_as_nxm<T: ExecutableUnifiedTable>(table: &T, nxm: &UnifiedNXMTable, inputs: usize, outputs: usize) {
+        let fuzz = TriangleFuzzyfier::with_min_max(-1.0,1.0);
+        let fuz : Vec<&Fuzzyfier> = vec![&fuzz; inputs];
+        let defuz : Vec<&Defuzzyfier> = vec![&fuzz; outputs];
+        let samples = vec![UnifiedToken::Phi, ut!(-1.0), ut!(-0.7), ut!(-0.2), ut!(0.0), ut!(0.33), ut!(0.9), ut!(1.5)];
+        let mut inps_list : Vec<Vec<UnifiedToken>> = samples.iter().map(|tk| vec![tk.clone()]).collect();
+        if inputs == 2 {
+            inps_list = samples.iter()
+                .flat_map(|f| samples.iter().map(move |s| vec![f.clone(), s.clone()]))
+                .collect();
+        }
+        for inps in inps_list {
+            assert_eq!(table.activation(&inps, &fuz), nxm.activation(&inps, &fuz));
+            let executable = table.is_executable(&inps, &fuz);
+            assert_eq!(executable, nxm.is_executable(&inps, &fuz));
+            if executable {
+                assert_eq!(table.execute(inps.clone(), &fuz, &defuz), nxm.execute(inps.clone(), &fuz, &defuz));
+            }
+        }
+        for marked in vec![vec![true, true], vec![true, false], vec![false, true], vec![false, false]] {
+            let marked = marked[..inputs].to_vec();
+            assert_eq!(table.possibly_executable(&marked), nxm.possibly_executable(&marked));
+        }
+    }
+
+    #[test]
+    fn nxm_matches_fixed_tables_test() {
+        let oxo = UnifiedOneXOneTable::default_table();
+        assert_same_as_nxm(&oxo, &UnifiedNXMTable::from(&oxo), 1, 1);
+        let oxo = UnifiedOneXOneTable::from_arr([E(PL), E(PM), E(NL), Phi , E(NL), E(ZR)]);
+        assert_same_as_nxm(&oxo, &UnifiedNXMTable::from(&oxo), 1, 1);
+
+        let oxt = UnifiedOneXTwoTable::default_table();
+        assert_same_as_nxm(&oxt, &UnifiedNXMTable::from(&oxt), 1, 2);
+
+        let txo = UnifiedTwoXOneTable::default_table();
+        assert_same_as_nxm(&txo, &UnifiedNXMTable::from(&txo), 2, 1);
+        for op in vec![Operator::Plus, Operator::Minus, Operator::Mult, Operator::Div] {
+            let txo = UnifiedTwoXOneTable::all_pl(op);
+            assert_same_as_nxm(&txo, &UnifiedNXMTable::from(&txo), 2, 1);
+            let txt = UnifiedTwoXTwoTable::all_pl(op);
+            assert_same_as_nxm(&txt, &UnifiedNXMTable::from(&txt), 2, 2);
+        }
+
+        let txt = UnifiedTwoXTwoTable::default_table();
+        assert_same_as_nxm(&txt, &UnifiedNXMTable::from(&txt), 2, 2);
+
+        // neither symmetric nor empty in the phi row and column, so swapped inputs or misplaced
+        // phi cells show up
+        let skewed = [
+            E(NL), E(NL), E(NM), E(ZR), E(PM), E(PL),
+            E(NM), Phi,   E(ZR), E(PM), E(PL), E(NL),
+            E(ZR), E(PM), Phi,   E(PL), E(PL), E(NM),
+            E(PM), E(PL), E(PL), Phi,   E(NL), E(ZR),
+            E(PL), E(PL), E(NL), E(NM), Phi,   Phi,
+            E(NL), E(ZR), Phi,   E(PM), E(NM), E(PL)];
+        let mut skewed_two = [Phi; 72];
+        for cell in 0..36 {
+            skewed_two[cell*2] = skewed[cell];
+            skewed_two[cell*2 + 1] = skewed[35 - cell];
+        }
+        for op in vec![Operator::NoOp, Operator::Plus, Operator::Minus, Operator::Div] {
+            let txo = UnifiedTwoXOneTable::from_arr(skewed, op);
+            assert_same_as_nxm(&txo, &UnifiedNXMTable::from(&txo), 2, 1);
+            let txt = UnifiedTwoXTwoTable::from_arr(skewed_two, op);
+            assert_same_as_nxm(&txt, &UnifiedNXMTable::from(&txt), 2, 2);
+        }
+    }
+
+    #[test]
+    fn NXMTable_three_inputs_test() {
+        let mut values = vec![Phi; 216];
+        // ZR ZR ZR
+        values[(2*6 + 2)*6 + 2] = E(PL);
+        // NL phi ZR
+        values[(0*6 + 5)*6 + 2] = E(NL);
+        let table = NXMTable::from_vec(3, 1, values);
+
+        let zr = t!(0.0, 0.0, 1.0, 0.0, 0.0);
+        assert!(table.is_executable(&vec![zr.clone(), zr.clone(), zr.clone()]));
+        assert!(!table.is_executable(&vec![zr.clone(), FuzzyToken::Phi, zr.clone()]));
+        assert!(table.is_executable(&vec![t!(0.5, 0.0, 0.5, 0.0, 0.0), FuzzyToken::Phi, zr.clone()]));
+        assert_eq!(table.execute(vec![t!(0.5, 0.0, 0.5, 0.0, 0.0), FuzzyToken::Phi, zr.clone()]),
+            vec![t!(1.0, 0.0, 0.0, 0.0, 0.0)]);
+        assert_eq!(table.execute(vec![zr.clone(), zr.clone(), t!(0.0, 0.0, 0.5, 0.5, 0.0)]),
+            vec![t!(0.0, 0.0, 0.0, 0.0, 1.0)]);
+
+        assert!(table.possibly_executable(&vec![true, true, true]));
+        assert!(table.possibly_executable(&vec![true, false, true]));
+        assert!(!table.possibly_executable(&vec![false, true, true]));
+
+        let fuzz = TriangleFuzzyfier::with_min_max(-1.0,1.0);
+        let plus = UnifiedNXMTable::from_vec(3, 1, table.values.clone(), Operator::Plus);
+        // PL scaled by the sum of the inputs
+        let rez = plus.execute(vec![ut!(0.1), ut!(0.0), ut!(0.0)], &vec![&fuzz, &fuzz, &fuzz], &vec![&fuzz]);
+        assert_eq!(rez, vec![ut!(0.1)]);
+    }
+
 }
@@ -0,0 +1,87 @@
+extern crate timer;
+extern crate chrono;
+
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::*;
+
+use unified_petri_net::net_executor::AsyncExecutorMsg;
+
+use self::timer::Timer;
+use self::chrono::Duration;
+
+/// Where the ticks of the threaded executor come from
+pub trait Clock : Send {
+    /// Starts sending `Tick` messages, they stop when the returned value is dropped
+    fn start(&mut self, ticks: Sender<AsyncExecutorMsg>) -> Box<Send>;
+}
+
+/// Ticks on the wall clock, every `dur`
+pub struct TimerClock {
+    dur: Duration,
+}
+
+impl TimerClock {
+    pub fn new(dur: Duration) -> TimerClock {
+        TimerClock { dur: dur }
+    }
+}
+
+impl Clock for TimerClock {
+    fn start(&mut self, ticks: Sender<AsyncExecutorMsg>) -> Box<Send> {
+        let timer = Timer::new();
+        let guard = timer.schedule_repeating(self.dur, move ||{
+             // fails only when the executor thread is gone, the executor guard reports that
+             let _ = ticks.send(AsyncExecutorMsg::Tick);
+        });
+        Box::new((timer, guard))
+    }
+}
+
+/// Ticks only when told to, a clone kept by the caller drives the executor
+#[derive(Clone)]
+pub struct ManualClock {
+    ticks: Arc<Mutex<Option<Sender<AsyncExecutorMsg>>>>,
+}
+
+struct ManualClockGuard {
+    ticks: Arc<Mutex<Option<Sender<AsyncExecutorMsg>>>>,
+}
+
+impl Drop for ManualClockGuard {
+    fn drop(&mut self) {
+        *self.ticks.lock().unwrap() = None;
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock { ticks: Arc::new(Mutex::new(None)) }
+    }
+
+    /// Sends `n` ticks and waits until the executor has processed them.
+    /// Returns false if the executor is not started, stopped or gone.
+    pub fn advance(&self, n: usize) -> bool {
+        let ticks = match *self.ticks.lock().unwrap() {
+            Some(ref ticks) => ticks.clone(),
+            None => return false,
+        };
+        for _ in 0..n {
+            if ticks.send(AsyncExecutorMsg::Tick).is_err() {
+                return false;
+            }
+        }
+        // messages are handled in order, the answer comes after the last tick
+        let (tx, rx) = channel();
+        if ticks.send(AsyncExecutorMsg::GetStatistics(tx)).is_err() {
+            return false;
+        }
+        rx.recv().is_ok()
+    }
+}
+
+impl Clock for ManualClock {
+    fn start(&mut self, ticks: Sender<AsyncExecutorMsg>) -> Box<Send> {
+        *self.ticks.lock().unwrap() = Some(ticks);
+        Box::new(ManualClockGuard { ticks: self.ticks.clone() })
+    }
+}
@@ -2,7 +2,7 @@ use std::io::prelude::*;
 use std::fs::File;
 use std::collections::HashMap;
 
-use unified_petri_net::net_builder::UnifiedPetriNet;
+use unified_petri_net::net_builder::{UnifiedPetriNet, UnifiedTableE};
 
 
 pub struct DotStringBuilder {
@@ -53,11 +53,25 @@ impl DotStringBuilder{
                 self.dot_string.push_str(&self.place_ids[&place_id]);
                 self.dot_string.push_str("\"->");
                 self.dot_string.push_str(&self.trans_ids[trans_id]);
+                let weight = DotStringBuilder::arc_weight(net, place_id, *trans_id);
+                if weight != 1.0 {
+                    self.dot_string.push_str("[label=\"");
+                    self.dot_string.push_str(&weight.to_string());
+                    self.dot_string.push_str("\"]");
+                }
                 self.dot_string.push_str(";\n");
             }
         }
     }
 
+    fn arc_weight(net : &UnifiedPetriNet, place_id: usize, trans_id: usize) -> f32 {
+        let places = net.get_places_befor_trans(trans_id);
+        match places.iter().position(|pl| *pl == place_id) {
+            Some(idx) => net.get_weights_befor_trans(trans_id)[idx],
+            None => 1.0,
+        }
+    }
+
     fn add_arcs_from_transs(&mut self, net : &UnifiedPetriNet) {
         for trans_id in 0..net.get_trans_nr() {
             let place_ids = net.get_places_after_trans(trans_id);
@@ -82,7 +96,13 @@ impl DotStringBuilder{
             self.dot_string.push_str(&tr_dot_id);
             self.dot_string.push_str("[label=\"\"xlabel=<<FONT POINT-SIZE='15'> ");
             self.dot_string.push_str(&tr_dot_label);
-            self.dot_string.push_str("</FONT>>];\n");
+            self.dot_string.push_str("</FONT>>");
+            if let Some(description) = net.get_trans_description(trans_id) {
+                self.dot_string.push_str(" tooltip=\"");
+                self.dot_string.push_str(&escape_quoted(description));
+                self.dot_string.push('"');
+            }
+            self.dot_string.push_str("];\n");
         }
         self.dot_string.push_str("}\n");
 
@@ -93,15 +113,25 @@ impl DotStringBuilder{
         if net.is_trans_out(trans_id){
             to_ret.push('o');
         };
-        to_ret.push('T');
-        to_ret.push_str(&trans_id.to_string());
+        to_ret.push_str(&escape_html(&net.trans_label(trans_id)));
 
         let delay =net.get_delay(trans_id) ;
-        if delay != 0 {
+        let multiplier = net.get_delay_multiplier(trans_id);
+        if multiplier != 0.0 {
+            to_ret.push('[');
+            to_ret.push_str(&delay.to_string());
+            to_ret.push('+');
+            to_ret.push_str(&multiplier.to_string());
+            to_ret.push_str("x]");
+        } else if delay != 0 {
             to_ret.push('[');
             to_ret.push_str(&delay.to_string());
             to_ret.push(']');
         };
+        // the fixed size tables follow from the arcs, a generic one is worth spelling out
+        if let UnifiedTableE::nxm(ref t) = *net.typed_table_for_trans(trans_id) {
+            to_ret.push_str(&format!("<SUB>{}x{}</SUB>", t.input_nr(), t.output_nr()));
+        }
         to_ret
     }
 
@@ -122,6 +152,19 @@ impl DotStringBuilder{
             self.dot_string.push('"');
             self.dot_string.push_str(&place_str_id);
             self.dot_string.push('"');
+            let mut attributes = vec![];
+            if let Some(name) = net.get_place_name(place_id) {
+                let label = DotStringBuilder::decorate_place(net, place_id, name);
+                attributes.push(format!("label=\"{}\"", escape_quoted(&label)));
+            }
+            if let Some(description) = net.get_place_description(place_id) {
+                attributes.push(format!("tooltip=\"{}\"", escape_quoted(description)));
+            }
+            if !attributes.is_empty() {
+                self.dot_string.push('[');
+                self.dot_string.push_str(&attributes.join(" "));
+                self.dot_string.push(']');
+            }
             self.dot_string.push(';');
 
         }
@@ -129,13 +172,17 @@ impl DotStringBuilder{
 
     }
 
+    /// Always built from the id, a name could clash with the id of another place
     fn create_place_id(net: &UnifiedPetriNet, place_id : usize) -> String {
+        DotStringBuilder::decorate_place(net, place_id, &format!("P{}", place_id))
+    }
+
+    fn decorate_place(net: &UnifiedPetriNet, place_id : usize, what: &str) -> String {
         let mut to_ret = String::from("");
         if net.is_place_inp(place_id) {
             to_ret.push('i');
         };
-        to_ret.push('P');
-        to_ret.push_str(&place_id.to_string());
+        to_ret.push_str(what);
 
         if net.get_initial_marking(place_id).not_phi() {
             to_ret.push('●');
@@ -145,6 +192,14 @@ impl DotStringBuilder{
 
 }
 
+fn escape_quoted(what: &str) -> String {
+    what.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_html(what: &str) -> String {
+    what.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -165,11 +220,12 @@ mod tests {
         bld.connect_transition_with_place(t0, p1);
         let t1 = bld.add_transition(2, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
         bld.connect_place_with_transition(p1, t1);
-        bld.connect_transition_with_place(t1, i_p0);
         let oT2 = bld.add_out_transition(UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
         bld.connect_place_with_transition(p1, oT2);
+        let p2 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t1, p2);
 
-        let (net,_) = bld.build();
+        let (net,_) = bld.build().unwrap();
         let dot_bld = DotStringBuilder::build(&net);
         assert!(dot_bld.dot_string.contains("iP0"));
         assert!(dot_bld.dot_string.contains("P1●"));
@@ -180,7 +236,94 @@ mod tests {
         assert!(dot_bld.dot_string.contains("\"P1●\"->t1") );
         assert!(dot_bld.dot_string.contains("\"P1●\"->t2") );
         assert!(dot_bld.dot_string.contains("t0->\"P1●\"") );
-        assert!(dot_bld.dot_string.contains("t1->\"iP0\"") );
+        assert!(dot_bld.dot_string.contains("t1->\"P2\"") );
+    }
+
+    #[test]
+    fn dot_builder_weight_test() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let i_p0 = bld.add_inp_place(1.0);
+        let p1 = bld.add_place(1.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::txo(UnifiedTwoXOneTable::default_table()));
+        bld.connect_place_with_transition(i_p0, t0);
+        bld.connect_place_with_transition_with_weight(p1, t0, 0.5);
+        let p2 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p2);
+
+        let (net,_) = bld.build().unwrap();
+        let dot_bld = DotStringBuilder::build(&net);
+        assert!(dot_bld.dot_string.contains("\"iP0\"->t0;"));
+        assert!(dot_bld.dot_string.contains("\"P1\"->t0[label=\"0.5\"];"));
+    }
+
+    #[test]
+    fn dot_builder_delay_multiplier_test() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let p0 = bld.add_place(1.0);
+        let t0 = bld.add_transition(2, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_delay_multiplier(t0, 1.5);
+        bld.connect_place_with_transition(p0, t0);
+        let p1 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p1);
+
+        let (net,_) = bld.build().unwrap();
+        let dot_bld = DotStringBuilder::build(&net);
+        assert!(dot_bld.dot_string.contains("T0[2+1.5x]"));
+    }
+
+    #[test]
+    fn dot_builder_names_test() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let i_p0 = bld.add_inp_place(1.0);
+        bld.set_place_name(i_p0, "temp_in");
+        bld.set_place_description(i_p0, "measured \"temperature\"");
+        let t0 = bld.add_transition(1, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_trans_name(t0, "heat<up>");
+        bld.connect_place_with_transition(i_p0, t0);
+        let p1 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p1);
+
+        let (net,_) = bld.build().unwrap();
+        let dot_bld = DotStringBuilder::build(&net);
+        assert!(dot_bld.dot_string.contains("\"iP0\"[label=\"itemp_in\" tooltip=\"measured \\\"temperature\\\"\"];"));
+        assert!(dot_bld.dot_string.contains("heat&lt;up&gt;[1]"));
+        assert!(dot_bld.dot_string.contains("\"iP0\"->t0;"));
+        assert!(dot_bld.dot_string.contains("t0->\"P1\""));
+    }
+
+    #[test]
+    fn dot_builder_name_clash_test() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let p0 = bld.add_place(1.0);
+        bld.set_place_name(p0, "P1");
+        let t0 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p0, t0);
+        let p1 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p1);
+
+        let (net,_) = bld.build().unwrap();
+        let dot_bld = DotStringBuilder::build(&net);
+        assert!(dot_bld.dot_string.contains("\"P0\"[label=\"P1\"];"));
+        assert!(dot_bld.dot_string.contains("\"P0\"->t0;"));
+        assert!(dot_bld.dot_string.contains("t0->\"P1\";"));
+    }
+
+    #[test]
+    fn dot_builder_nxm_table_test() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let places : Vec<usize> = (0..3).map(|_| bld.add_inp_place(1.0)).collect();
+        let table = UnifiedNXMTable::from_vec(3, 1, vec![TableValue::Phi; 216], Operator::Plus);
+        let t0 = bld.add_transition(1, UnifiedTableE::nxm(table));
+        for pl in places {
+            bld.connect_place_with_transition(pl, t0);
+        }
+        let p3 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p3);
+
+        let (net,_) = bld.build().unwrap();
+        let dot_bld = DotStringBuilder::build(&net);
+        assert!(dot_bld.dot_string.contains("T0[1]<SUB>3x1</SUB>"));
+        assert!(dot_bld.dot_string.contains("\"iP2\"->t0;"));
     }
 
 }
@@ -1,9 +1,23 @@
 mod net_builder;
 mod net_executor;
 mod dot_string_builder;
+mod trace;
+mod snapshot;
+mod statistics;
+mod clock;
 
-pub use self::net_builder::{UnifiedPetriNet, UnifiedPetriNetBuilder, UnifiedTableE};
+pub use self::net_builder::{UnifiedPetriNet, UnifiedPetriNetBuilder, UnifiedTableE,
+    StructuralProblem, HandlerError, NamedInputError};
 pub use self::net_builder::{EventManager, UnifiedTokenConsumer};
 pub use self::net_executor::{SynchronousUnifiedPetriExecutor, AsynchronousUnifiedPetriExecutor,
-    AsynchronousThreadedUnifiedPetriExecutor, AsyncExecutorMsg, ExecutorGuard};
+    BatchUnifiedPetriExecutor, UnifiedPetriDebugger, Breakpoint, DebugStep, StepEvent, FiringReport,
+    AsynchronousThreadedUnifiedPetriExecutor, AsyncExecutorMsg, ExecutorGuard,
+    LoopLimitDiagnostic, LoopLimitHandler, DEFAULT_MAX_LOOP, TickResult, ConflictPolicy,
+    HotSwapError, InputError, InputPolicy, ExecutionError, NaNSource, FaultPolicy,
+    ExecutorSettings};
 pub use self::dot_string_builder::DotStringBuilder;
+pub use self::trace::{TraceRecorder, TickTrace, FiringStart, FiringFinish, ConflictChoice,
+    write_json_lines};
+pub use self::snapshot::{ExecutorSnapshot, SnapshotError};
+pub use self::statistics::{ExecutorStatistics, TransitionStatistics};
+pub use self::clock::{Clock, TimerClock, ManualClock};
@@ -1,15 +1,19 @@
 
 use tables::*;
 use basic::*;
+use net_structure::NetStructure;
 use std::collections::HashMap;
+use std::fmt;
+use std::mem;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum UnifiedTableE{
     oxo(UnifiedOneXOneTable),
     oxt(UnifiedOneXTwoTable),
     txo(UnifiedTwoXOneTable),
     txt(UnifiedTwoXTwoTable),
+    nxm(UnifiedNXMTable),
 }
 
 impl UnifiedTableE {
@@ -19,39 +23,167 @@ impl UnifiedTableE {
           UnifiedTableE::oxt(ref t) => t,
           UnifiedTableE::txo(ref t) => t,
           UnifiedTableE::txt(ref t) => t,
+          UnifiedTableE::nxm(ref t) => t,
         }
     }
+
+    pub fn input_nr(&self) -> usize {
+        match *self {
+          UnifiedTableE::oxo(_) | UnifiedTableE::oxt(_) => 1,
+          UnifiedTableE::txo(_) | UnifiedTableE::txt(_) => 2,
+          UnifiedTableE::nxm(ref t) => t.input_nr(),
+        }
+    }
+
+    pub fn output_nr(&self) -> usize {
+        match *self {
+          UnifiedTableE::oxo(_) | UnifiedTableE::txo(_) => 1,
+          UnifiedTableE::oxt(_) | UnifiedTableE::txt(_) => 2,
+          UnifiedTableE::nxm(ref t) => t.output_nr(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructuralProblem {
+    WrongInputNr{tr_id: usize, expected: usize, found: usize},
+    WrongOutputNr{tr_id: usize, expected: usize, found: usize},
+    OutTransitionTable{tr_id: usize},
+    ArcOutOfRange{tr_id: usize, pl_id: usize},
+    ArcIntoInputPlace{tr_id: usize, pl_id: usize},
+    UnconnectedPlace{pl_id: usize},
+    DuplicatePlaceName{name: String},
+    DuplicateTransName{name: String},
+    /// only the json reader can find this one, the builder keeps a single arc list
+    PlaceArcsDisagree{pl_id: usize, listed: Vec<usize>, needed: Vec<usize>},
 }
 
-#[derive(Debug, Clone)]
+impl StructuralProblem {
+    /// Renders the problem with the given place and transition labels
+    pub fn describe(&self, pl: &Fn(usize) -> String, tr: &Fn(usize) -> String) -> String {
+        use self::StructuralProblem::*;
+        match *self {
+            WrongInputNr{tr_id, expected, found} =>
+                format!("{} has {} input places, its table needs {}", tr(tr_id), found, expected),
+            WrongOutputNr{tr_id, expected, found} =>
+                format!("{} has {} output places, its table needs {}", tr(tr_id), found, expected),
+            OutTransitionTable{tr_id} =>
+                format!("{} is an output transition, it needs a 1x1 table", tr(tr_id)),
+            ArcOutOfRange{tr_id, pl_id} =>
+                format!("arc between T{} and P{} points to a missing node", tr_id, pl_id),
+            ArcIntoInputPlace{tr_id, pl_id} =>
+                format!("{} puts tokens into input place {}", tr(tr_id), pl(pl_id)),
+            UnconnectedPlace{pl_id} =>
+                format!("{} is not connected to any transition", pl(pl_id)),
+            DuplicatePlaceName{ref name} =>
+                format!("more places are named {}", name),
+            DuplicateTransName{ref name} =>
+                format!("more transitions are named {}", name),
+            PlaceArcsDisagree{pl_id, ref listed, ref needed} =>
+                format!("{} is listed before {:?}, but transitions {:?} need it", pl(pl_id), listed, needed),
+        }
+    }
+}
+
+impl fmt::Display for StructuralProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.describe(&|pl_id| format!("P{}", pl_id), &|tr_id| format!("T{}", tr_id)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandlerError {
+    NoSuchTransition(String),
+    NotAnOutTransition(usize),
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HandlerError::NoSuchTransition(ref name) => write!(f, "there is no transition named {}", name),
+            HandlerError::NotAnOutTransition(tr_id) => write!(f, "T{} is not an output transition", tr_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamedInputError {
+    NoSuchPlace(String),
+    NotAnInputPlace(String),
+}
+
+impl fmt::Display for NamedInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NamedInputError::NoSuchPlace(ref name) => write!(f, "there is no place named {}", name),
+            NamedInputError::NotAnInputPlace(ref name) => write!(f, "{} is not an input place", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Trans{
     delay: i32,
+    delay_multiplier: f32,
+    priority: i32,
     table: UnifiedTableE,
     is_out: bool,
     before_places: Vec<usize>,
+    before_weights: Vec<f32>,
     after_places: Vec<usize>,
+    name: Option<String>,
+    description: Option<String>,
 }
 
 impl Trans {
+    /// What would be wrong with `table` as the table of this transition
+    fn table_problems(&self, tr_id: usize, table: &UnifiedTableE) -> Vec<StructuralProblem> {
+        let mut problems = vec![];
+        if self.is_out {
+            if let UnifiedTableE::oxo(_) = *table {} else {
+                problems.push(StructuralProblem::OutTransitionTable{tr_id: tr_id});
+            }
+        }
+        let expected_inps = if self.is_out { 1 } else { table.input_nr() };
+        if self.before_places.len() != expected_inps {
+            problems.push(StructuralProblem::WrongInputNr{
+                tr_id: tr_id, expected: expected_inps, found: self.before_places.len()});
+        }
+        let expected_outs = if self.is_out { 0 } else { table.output_nr() };
+        if self.after_places.len() != expected_outs {
+            problems.push(StructuralProblem::WrongOutputNr{
+                tr_id: tr_id, expected: expected_outs, found: self.after_places.len()});
+        }
+        problems
+    }
+
     pub fn new(delay : i32, table: UnifiedTableE, out : bool) -> Trans{
          Trans{
             delay: delay,
+            delay_multiplier: 0.0,
+            priority: 0,
             is_out: out,
             table: table,
             before_places : vec![],
+            before_weights : vec![],
             after_places : vec![],
+            name: None,
+            description: None,
         }
 
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Place{
     scale: f32,
     is_inp: bool,
     inital_marking: UnifiedToken,
+    merge: MergeStrategy,
     before_transition: Vec<usize>,
     after_transition: Vec<usize>,
+    name: Option<String>,
+    description: Option<String>,
 }
 
 impl Place {
@@ -60,8 +192,11 @@ impl Place {
             scale: scale,
             is_inp :inp,
             inital_marking : UnifiedToken::Phi,
+            merge: MergeStrategy::default(),
             before_transition : vec![],
             after_transition : vec![],
+            name: None,
+            description: None,
         }
     }
 
@@ -95,7 +230,7 @@ impl EventManager {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnifiedPetriNet {
     transitions : Vec<Trans>,
     places : Vec<Place>,
@@ -105,6 +240,7 @@ pub struct UnifiedPetriNetBuilder {
     transitions : Vec<Trans>,
     places : Vec<Place>,
     manager: EventManager,
+    bad_arcs: Vec<StructuralProblem>,
 }
 
 impl UnifiedPetriNetBuilder {
@@ -114,6 +250,7 @@ impl UnifiedPetriNetBuilder {
             transitions : vec![],
             places: vec![],
             manager: EventManager::new(),
+            bad_arcs: vec![],
         }
     }
 
@@ -123,10 +260,6 @@ impl UnifiedPetriNetBuilder {
     }
 
     pub fn add_out_transition(&mut self,  table: UnifiedTableE ) -> usize {
-        match table {
-            UnifiedTableE::oxo(_) => {/* nothing */},
-            _ => panic!("wrong table type for out transition")
-        }
         self.transitions.push(Trans::new(0, table, true));
         self.transitions.len() - 1
     }
@@ -142,29 +275,149 @@ impl UnifiedPetriNetBuilder {
     }
 
     pub fn connect_transition_with_place(&mut self, tr : usize, pl : usize)  {
+        if !self.arc_in_range(tr, pl) {
+            return;
+        }
         self.transitions[tr].after_places.push(pl);
         self.places[pl].before_transition.push(tr);
     }
 
     pub fn connect_place_with_transition(&mut self,  pl : usize, tr: usize)  {
+        self.connect_place_with_transition_with_weight(pl, tr, 1.0);
+    }
+
+    pub fn connect_place_with_transition_with_weight(&mut self,  pl : usize, tr: usize,
+                                                     weight: f32)  {
+        if !self.arc_in_range(tr, pl) {
+            return;
+        }
         self.transitions[tr].before_places.push(pl);
+        self.transitions[tr].before_weights.push(weight);
         self.places[pl].after_transition.push(tr);
     }
 
+    pub fn set_delay_multiplier(&mut self, tr: usize, multiplier: f32) {
+        self.transitions[tr].delay_multiplier = multiplier;
+    }
+
+    /// Among fireable transitions competing in the same step the higher priority wins, the default is 0
+    pub fn set_priority(&mut self, tr: usize, priority: i32) {
+        self.transitions[tr].priority = priority;
+    }
+
     pub fn set_innitial_marking(&mut self, pl: usize, token : UnifiedToken ) {
         self.places[pl].inital_marking = token;
     }
 
+    /// How the place combines the tokens arriving on it, `Unite` if not set
+    pub fn set_merge_strategy(&mut self, pl: usize, strategy: MergeStrategy) {
+        self.places[pl].merge = strategy;
+    }
+
+    pub fn set_place_name(&mut self, pl: usize, name: &str) {
+        self.places[pl].name = Some(name.to_string());
+    }
+
+    pub fn set_place_description(&mut self, pl: usize, description: &str) {
+        self.places[pl].description = Some(description.to_string());
+    }
+
+    pub fn set_trans_name(&mut self, tr: usize, name: &str) {
+        self.transitions[tr].name = Some(name.to_string());
+    }
+
+    pub fn set_trans_description(&mut self, tr: usize, description: &str) {
+        self.transitions[tr].description = Some(description.to_string());
+    }
+
+    pub fn place_by_name(&self, name: &str) -> Option<usize> {
+        self.places.iter().position(|pl| pl.name.as_ref().map_or(false, |n| n == name))
+    }
+
+    pub fn trans_by_name(&self, name: &str) -> Option<usize> {
+        self.transitions.iter().position(|tr| tr.name.as_ref().map_or(false, |n| n == name))
+    }
+
     pub fn add_action_for_out_trans(&mut self, tr_id: usize, f : Box<UnifiedTokenConsumer>) {
         self.manager.add(tr_id, f);
     }
 
-    pub fn build(self) ->(UnifiedPetriNet, EventManager) {
+    /// Returns the id of the transition, a handler on an internal transition would never be called
+    pub fn add_action_for_named_out_trans(&mut self, name: &str, f : Box<UnifiedTokenConsumer>)
+        -> Result<usize, HandlerError> {
+        let tr_id = self.trans_by_name(name).ok_or_else(|| HandlerError::NoSuchTransition(name.to_string()))?;
+        if !self.transitions[tr_id].is_out {
+            return Err(HandlerError::NotAnOutTransition(tr_id));
+        }
+        self.manager.add(tr_id, f);
+        Ok(tr_id)
+    }
+
+    pub fn describe_problem(&self, problem: &StructuralProblem) -> String {
+        problem.describe(&|pl_id| node_label(self.places.get(pl_id).and_then(|p| p.name.as_ref()), 'P', pl_id),
+                         &|tr_id| node_label(self.transitions.get(tr_id).and_then(|t| t.name.as_ref()), 'T', tr_id))
+    }
+
+    fn arc_in_range(&mut self, tr: usize, pl: usize) -> bool {
+        let in_range = tr < self.transitions.len() && pl < self.places.len();
+        if !in_range {
+            self.bad_arcs.push(StructuralProblem::ArcOutOfRange{tr_id: tr, pl_id: pl});
+        }
+        in_range
+    }
+
+    /// Lists every structural problem, an empty list means the net can be executed
+    pub fn validate(&self) -> Vec<StructuralProblem> {
+        let mut problems = self.bad_arcs.clone();
+        for (tr_id, tr) in self.transitions.iter().enumerate() {
+            problems.extend(tr.table_problems(tr_id, &tr.table));
+        }
+        for (pl_id, pl) in self.places.iter().enumerate() {
+            if pl.is_inp {
+                for tr_id in &pl.before_transition {
+                    problems.push(StructuralProblem::ArcIntoInputPlace{tr_id: *tr_id, pl_id: pl_id});
+                }
+            }
+            if pl.before_transition.is_empty() && pl.after_transition.is_empty() {
+                problems.push(StructuralProblem::UnconnectedPlace{pl_id: pl_id});
+            }
+        }
+        for name in duplicates(self.places.iter().filter_map(|pl| pl.name.as_ref())) {
+            problems.push(StructuralProblem::DuplicatePlaceName{name: name});
+        }
+        for name in duplicates(self.transitions.iter().filter_map(|tr| tr.name.as_ref())) {
+            problems.push(StructuralProblem::DuplicateTransName{name: name});
+        }
+        problems
+    }
+
+    pub fn build(self) -> Result<(UnifiedPetriNet, EventManager), Vec<StructuralProblem>> {
+        let problems = self.validate();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
         let net = UnifiedPetriNet{
             places : self.places,
             transitions : self.transitions,
         };
-        (net, self.manager)
+        Ok((net, self.manager))
+    }
+}
+
+fn duplicates<'a, I: Iterator<Item=&'a String>>(names: I) -> Vec<String> {
+    let mut seen = HashMap::new();
+    for name in names {
+        *seen.entry(name.clone()).or_insert(0) += 1;
+    }
+    let mut to_ret : Vec<String> = seen.into_iter().filter(|&(_, cnt)| cnt > 1).map(|(name, _)| name).collect();
+    to_ret.sort();
+    to_ret
+}
+
+fn node_label(name: Option<&String>, prefix: char, id: usize) -> String {
+    match name {
+        Some(name) => name.clone(),
+        None => format!("{}{}", prefix, id),
     }
 }
 
@@ -195,6 +448,20 @@ impl UnifiedPetriNet {
         self.places[pl_id].scale
     }
 
+    /// Swaps the table of a transition for one that fits its arcs, returns the old table
+    pub fn replace_table(&mut self, tr_id: usize, table: UnifiedTableE)
+        -> Result<UnifiedTableE, Vec<StructuralProblem>> {
+        let problems = self.transitions[tr_id].table_problems(tr_id, &table);
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+        Ok(mem::replace(&mut self.transitions[tr_id].table, table))
+    }
+
+    pub fn set_place_scale(&mut self, pl_id: usize, scale: f32) {
+        self.places[pl_id].scale = scale;
+    }
+
     #[inline]
     pub fn table_for_trans(&self, tr_id: usize) ->&ExecutableUnifiedTable {
         self.transitions[tr_id].table.get_table()
@@ -220,14 +487,154 @@ impl UnifiedPetriNet {
         &self.transitions[tr_id].before_places
     }
 
+    #[inline]
+    pub fn get_weights_befor_trans(&self, tr_id: usize) -> &Vec<f32> {
+        &self.transitions[tr_id].before_weights
+    }
+
     #[inline]
     pub fn get_initial_marking(&self, pl_id: usize) -> UnifiedToken {
         self.places[pl_id].inital_marking.clone()
     }
 
+    pub fn get_merge_strategy(&self, pl_id: usize) -> MergeStrategy {
+        self.places[pl_id].merge
+    }
+
     #[inline]
     pub fn get_delay(&self, tr_id: usize) -> i32 {
         self.transitions[tr_id].delay
     }
 
+    #[inline]
+    pub fn get_delay_multiplier(&self, tr_id: usize) -> f32 {
+        self.transitions[tr_id].delay_multiplier
+    }
+
+    pub fn get_priority(&self, tr_id: usize) -> i32 {
+        self.transitions[tr_id].priority
+    }
+
+    #[inline]
+    pub fn has_delay(&self, tr_id: usize) -> bool {
+        self.transitions[tr_id].delay != 0 || self.transitions[tr_id].delay_multiplier != 0.0
+    }
+
+    #[inline]
+    pub fn get_place_name(&self, pl_id: usize) -> Option<&str> {
+        self.places[pl_id].name.as_ref().map(|n| n.as_str())
+    }
+
+    #[inline]
+    pub fn get_trans_name(&self, tr_id: usize) -> Option<&str> {
+        self.transitions[tr_id].name.as_ref().map(|n| n.as_str())
+    }
+
+    #[inline]
+    pub fn get_place_description(&self, pl_id: usize) -> Option<&str> {
+        self.places[pl_id].description.as_ref().map(|n| n.as_str())
+    }
+
+    #[inline]
+    pub fn get_trans_description(&self, tr_id: usize) -> Option<&str> {
+        self.transitions[tr_id].description.as_ref().map(|n| n.as_str())
+    }
+
+    /// The name of the place, or `P<id>` when it has none
+    pub fn place_label(&self, pl_id: usize) -> String {
+        node_label(self.places[pl_id].name.as_ref(), 'P', pl_id)
+    }
+
+    /// The name of the transition, or `T<id>` when it has none
+    pub fn trans_label(&self, tr_id: usize) -> String {
+        node_label(self.transitions[tr_id].name.as_ref(), 'T', tr_id)
+    }
+
+    pub fn place_by_name(&self, name: &str) -> Option<usize> {
+        self.places.iter().position(|pl| pl.name.as_ref().map_or(false, |n| n == name))
+    }
+
+    pub fn trans_by_name(&self, name: &str) -> Option<usize> {
+        self.transitions.iter().position(|tr| tr.name.as_ref().map_or(false, |n| n == name))
+    }
+
+    /// Turns `(place name, token)` pairs into the `(place id, token)` pairs the executors take,
+    /// the first name that is unknown or not an input place is the error
+    pub fn named_inputs(&self, inps: Vec<(&str, UnifiedToken)>)
+        -> Result<Vec<(usize, UnifiedToken)>, NamedInputError> {
+        inps.into_iter()
+            .map(|(name, tk)| match self.place_by_name(name) {
+                None => Err(NamedInputError::NoSuchPlace(name.to_string())),
+                Some(pl_id) if !self.is_place_inp(pl_id) => Err(NamedInputError::NotAnInputPlace(name.to_string())),
+                Some(pl_id) => Ok((pl_id, tk)),
+            })
+            .collect()
+    }
+
+}
+
+
+impl NetStructure for UnifiedPetriNet {
+    fn get_trans_nr(&self) -> usize {
+        UnifiedPetriNet::get_trans_nr(self)
+    }
+
+    fn get_places_befor_trans(&self, tr_id: usize) -> &Vec<usize> {
+        UnifiedPetriNet::get_places_befor_trans(self, tr_id)
+    }
+
+    fn is_place_inp(&self, pl_id: usize) -> bool {
+        UnifiedPetriNet::is_place_inp(self, pl_id)
+    }
+
+    fn is_trans_out(&self, tr_id: usize) -> bool {
+        UnifiedPetriNet::is_trans_out(self, tr_id)
+    }
+
+    fn has_delay(&self, tr_id: usize) -> bool {
+        UnifiedPetriNet::has_delay(self, tr_id)
+    }
+
+    fn get_priority(&self, tr_id: usize) -> i32 {
+        UnifiedPetriNet::get_priority(self, tr_id)
+    }
+
+    fn possibly_executable(&self, tr_id: usize, marked: &Vec<bool>) -> bool {
+        self.table_for_trans(tr_id).possibly_executable(marked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn validate_lists_every_problem() {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let i_p0 = bld.add_inp_place(1.0);
+        let p1 = bld.add_place(1.0);
+        let p2 = bld.add_place(1.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::txo(UnifiedTwoXOneTable::default_table()));
+        bld.connect_place_with_transition(i_p0, t0);
+        bld.connect_transition_with_place(t0, p1);
+        let t1 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, t1);
+        bld.connect_transition_with_place(t1, i_p0);
+        let ot2 = bld.add_out_transition(UnifiedTableE::oxt(UnifiedOneXTwoTable::default_table()));
+        bld.connect_place_with_transition(p1, ot2);
+        bld.connect_place_with_transition(7, ot2);
+
+        let problems = bld.validate();
+        assert_eq!(problems, vec![
+            StructuralProblem::ArcOutOfRange{tr_id: ot2, pl_id: 7},
+            StructuralProblem::WrongInputNr{tr_id: t0, expected: 2, found: 1},
+            StructuralProblem::OutTransitionTable{tr_id: ot2},
+            StructuralProblem::ArcIntoInputPlace{tr_id: t1, pl_id: i_p0},
+            StructuralProblem::UnconnectedPlace{pl_id: p2},
+        ]);
+        assert_eq!(format!("{}", problems[1]), "T0 has 1 input places, its table needs 2");
+        assert!(bld.build().is_err());
+    }
+
 }
@@ -1,136 +1,793 @@
 
 extern crate fnv;
-extern crate timer;
 extern crate chrono;
 
 
 
-use unified_petri_net::net_builder::{UnifiedPetriNet, EventManager};
+use unified_petri_net::net_builder::{UnifiedPetriNet, EventManager, UnifiedTableE, StructuralProblem};
+use unified_petri_net::trace::{TraceRecorder, TickTrace, FiringStart, FiringFinish};
+use unified_petri_net::snapshot::{ExecutorSnapshot, SnapshotError};
+use unified_petri_net::statistics::ExecutorStatistics;
+use unified_petri_net::clock::{Clock, TimerClock};
 use basic::*;
+use net_structure::{order_of_transitions, possibly_executable_trans};
 use std::mem;
+use std::fmt;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use self::fnv::FnvHasher;
 use std::hash::BuildHasherDefault;
 use std::sync::mpsc::*;
+use std::sync::{Arc, RwLock};
 use std::thread;
+use std::thread::JoinHandle;
+use std::io::Write;
+use std::time::{self, Instant};
 
 
-use self::timer::*;
 use self::chrono::Duration;
 
 type MyHasher = BuildHasherDefault<FnvHasher>;
 
+/// Reported when `execute_firable_transitions` stops at the loop limit with fireable transitions left.
+/// `cycle` is the chain of zero delay transitions that keep enabling each other,
+/// it is empty when the net just needs more iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopLimitDiagnostic {
+    pub max_loop: usize,
+    pub fireable: Vec<usize>,
+    pub cycle: Vec<usize>,
+}
+
+impl LoopLimitDiagnostic {
+    pub fn is_livelock(&self) -> bool {
+        !self.cycle.is_empty()
+    }
+}
+
+pub trait LoopLimitHandler : Send {
+    fn loop_limit_hit(&mut self, diag: &LoopLimitDiagnostic);
+}
+
+pub use net_structure::DEFAULT_MAX_LOOP;
+
+/// The tokens the output transitions produced during a tick, in firing order
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickResult {
+    pub outputs: Vec<(usize, UnifiedToken)>,
+}
+
+/// Why a table or a scale could not be changed on an executor, which is then left unchanged
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotSwapError {
+    NoSuchTransition(usize),
+    NoSuchPlace(usize),
+    TableDoesNotFit(Vec<StructuralProblem>),
+    /// scales have to be finite and positive
+    WrongScale(f32),
+}
+
+impl fmt::Display for HotSwapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HotSwapError::NoSuchTransition(tr_id) => write!(f, "no transition with id {}", tr_id),
+            HotSwapError::NoSuchPlace(pl_id) => write!(f, "no place with id {}", pl_id),
+            HotSwapError::TableDoesNotFit(ref problems) => {
+                let msgs : Vec<String> = problems.iter().map(|p| format!("{}", p)).collect();
+                write!(f, "the table does not fit: {}", msgs.join(", "))
+            },
+            HotSwapError::WrongScale(scale) => write!(f, "wrong scale {}", scale),
+        }
+    }
+}
+
+/// An input token addressed to a place the net does not take inputs in
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    NoSuchPlace(usize),
+    NotAnInputPlace(usize),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::NoSuchPlace(pl_id) => write!(f, "no place with id {}", pl_id),
+            InputError::NotAnInputPlace(pl_id) => write!(f, "P{} is not an input place", pl_id),
+        }
+    }
+}
+
+/// Where a NaN token came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NaNSource {
+    /// the table of the transition produced it, a division by zero for example
+    Transition(usize),
+    /// it was given for the input place, a sensor reading gone wrong
+    InputPlace(usize),
+}
+
+/// Why an executor could not run a tick or an injection the way the net says
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionError {
+    /// the table of the transition takes or gives a different number of tokens than it has arcs
+    ArityMismatch{tr_id: usize, expected: usize, found: usize},
+    NaNToken(NaNSource),
+    InvalidInput(InputError),
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExecutionError::ArityMismatch{tr_id, expected, found} =>
+                write!(f, "T{} has {} tokens where {} are needed", tr_id, found, expected),
+            ExecutionError::NaNToken(NaNSource::Transition(tr_id)) => write!(f, "T{} produced a NaN token", tr_id),
+            ExecutionError::NaNToken(NaNSource::InputPlace(pl_id)) => write!(f, "NaN input for P{}", pl_id),
+            ExecutionError::InvalidInput(ref err) => write!(f, "invalid input: {}", err),
+        }
+    }
+}
+
+impl From<InputError> for ExecutionError {
+    fn from(err: InputError) -> ExecutionError {
+        ExecutionError::InvalidInput(err)
+    }
+}
+
+/// What the executor does when a firing goes wrong, every fault is counted in the statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FaultPolicy {
+    /// the tick stops at the fault and the error is returned, what fired before stays fired,
+    /// a NaN input rejects all the inputs of the tick
+    Abort,
+    /// the faulty transition consumes its inputs and produces nothing, NaN inputs are dropped
+    SkipTransition,
+    /// NaN outputs become phi, NaN inputs are dropped, other faults skip the transition
+    NaNToPhi,
+}
+
+impl Default for FaultPolicy {
+    fn default() -> FaultPolicy {
+        FaultPolicy::Abort
+    }
+}
+
+/// What the executor does with the inputs when some are not addressed to input places.
+/// Either way they are counted in the statistics, `inject_token` is the way to fill other places.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputPolicy {
+    /// none of the inputs are used and nothing runs, the tick is refused
+    Reject,
+    /// the wrong tokens are dropped, the rest is used
+    Ignore,
+}
+
+impl Default for InputPolicy {
+    fn default() -> InputPolicy {
+        InputPolicy::Reject
+    }
+}
+
+/// How the executor picks among the fireable transitions that compete for an input place.
+/// A higher transition priority always wins, the policy breaks the ties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /// the first in the executor's order: input connected, out, zero delay, then delayed transitions
+    StaticPriority,
+    /// the first after the winner of the previous conflict in the executor's order
+    RoundRobin,
+    /// xorshift from the given seed, so runs can be repeated
+    SeededRandom(u64),
+    /// the one whose table fires its strongest rule the strongest, ties go by the executor's order
+    HighestActivation,
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> ConflictPolicy {
+        ConflictPolicy::StaticPriority
+    }
+}
+
+impl ConflictPolicy {
+    /// The name written to the trace
+    pub fn name(&self) -> String {
+        match *self {
+            ConflictPolicy::StaticPriority => "staticPriority".to_string(),
+            ConflictPolicy::RoundRobin => "roundRobin".to_string(),
+            ConflictPolicy::SeededRandom(seed) => format!("seededRandom({})", seed),
+            ConflictPolicy::HighestActivation => "highestActivation".to_string(),
+        }
+    }
+}
+
+/// How an executor runs, every executor takes it through `set_settings`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExecutorSettings {
+    /// rounds of firing after a tick or an input
+    pub max_loop: usize,
+    pub conflict_policy: ConflictPolicy,
+    pub input_policy: InputPolicy,
+    pub fault_policy: FaultPolicy,
+}
+
+impl Default for ExecutorSettings {
+    fn default() -> ExecutorSettings {
+        ExecutorSettings {
+            max_loop: DEFAULT_MAX_LOOP,
+            conflict_policy: ConflictPolicy::default(),
+            input_policy: InputPolicy::default(),
+            fault_policy: FaultPolicy::default(),
+        }
+    }
+}
+
+type PossiblyExecCache = RwLock<HashMap<Vec<bool>, Vec<usize>,MyHasher>>;
+
+/// The net and what is derived from it are shared, so the instances of a batch keep one copy
 struct BasicUnifiedPetriExecutor {
-    net: UnifiedPetriNet,
+    net: Arc<UnifiedPetriNet>,
     event_manager: EventManager,
     place_state: Vec<UnifiedToken>,
+    place_arrivals: Vec<u32>,
     trans_state: Vec<i32>,
     trans_holds: Vec<Vec<UnifiedToken>>,
-    trans_order: Vec<usize>,
-    scales : Vec<TriangleFuzzyfier>,
-    cached_possibly_exec : HashMap<Vec<bool>, Vec<usize>,MyHasher>,
+    trans_order: Arc<Vec<usize>>,
+    order_pos: Arc<Vec<usize>>,
+    scales : Arc<Vec<TriangleFuzzyfier>>,
+    cached_possibly_exec : Arc<PossiblyExecCache>,
+    settings: ExecutorSettings,
+    loop_limit_handler: Option<Box<LoopLimitHandler>>,
+    trace: Option<TraceRecorder>,
+    outputs: Vec<(usize, UnifiedToken)>,
+    stats: ExecutorStatistics,
+    paused: bool,
+    last_winner: Option<usize>,
+    rng_state: u64,
 }
 
 impl BasicUnifiedPetriExecutor {
 
     pub fn new(net : UnifiedPetriNet, men: EventManager) -> BasicUnifiedPetriExecutor {
+        let trans_order = order_of_transitions(&net);
+        let mut order_pos = vec![0; net.get_trans_nr()];
+        for (pos, tr_id) in trans_order.iter().enumerate() {
+            order_pos[*tr_id] = pos;
+        }
         BasicUnifiedPetriExecutor{
-            scales :init_scales(&net),
-            trans_order: order_of_transitions(&net),
+            scales : Arc::new(init_scales(&net)),
+            trans_order: Arc::new(trans_order),
+            order_pos: Arc::new(order_pos),
             place_state: init_place_state(&net),
+            place_arrivals: init_place_arrivals(&net),
             trans_state: vec![0; net.get_trans_nr()],
             trans_holds: vec![vec![]; net.get_trans_nr()],
             event_manager: men,
-            cached_possibly_exec: HashMap::default(),
-            net: net,
+            cached_possibly_exec: Arc::new(RwLock::new(HashMap::default())),
+            settings: ExecutorSettings::default(),
+            loop_limit_handler: None,
+            trace: None,
+            outputs: vec![],
+            stats: ExecutorStatistics::for_transitions(net.get_trans_nr()),
+            paused: false,
+            last_winner: None,
+            rng_state: 0,
+            net: Arc::new(net),
         }
     }
 
-    pub fn put_tokens_to_inp_places(&mut self, inps: Vec<(usize, UnifiedToken)>) {
+    /// An executor in the initial marking that shares the net and the caches with this one,
+    /// the settings are copied, the trace and the loop limit handler are not
+    pub fn new_instance(&self, men: EventManager) -> BasicUnifiedPetriExecutor {
+        let mut instance = BasicUnifiedPetriExecutor{
+            net: self.net.clone(),
+            event_manager: men,
+            place_state: init_place_state(&self.net),
+            place_arrivals: init_place_arrivals(&self.net),
+            trans_state: vec![0; self.net.get_trans_nr()],
+            trans_holds: vec![vec![]; self.net.get_trans_nr()],
+            trans_order: self.trans_order.clone(),
+            order_pos: self.order_pos.clone(),
+            scales: self.scales.clone(),
+            cached_possibly_exec: self.cached_possibly_exec.clone(),
+            settings: self.settings,
+            loop_limit_handler: None,
+            trace: None,
+            outputs: vec![],
+            stats: ExecutorStatistics::for_transitions(self.net.get_trans_nr()),
+            paused: false,
+            last_winner: None,
+            rng_state: 0,
+        };
+        instance.set_settings(self.settings);
+        instance
+    }
+
+    /// Nothing is put if the input policy rejects the inputs or the fault policy aborts on a NaN
+    pub fn put_tokens_to_inp_places(&mut self, inps: Vec<(usize, UnifiedToken)>) -> Result<(), ExecutionError> {
+        let inps = self.check_inputs(inps)?;
+        if let Some(ref mut trace) = self.trace {
+            trace.record_inputs(&inps);
+        }
+        self.stats.input_tokens += inps.len() as u64;
         for (pl_id, tk) in inps.into_iter() {
-            self.place_state[pl_id].unite(tk);
+            self.merge_into(pl_id, tk);
         }
+        Ok(())
     }
 
-    pub fn update_delay_state(&mut self) {
+    /// The inputs the input and the fault policies let through, or the error for the first wrong one
+    pub fn check_inputs(&mut self, inps: Vec<(usize, UnifiedToken)>)
+        -> Result<Vec<(usize, UnifiedToken)>, ExecutionError> {
+        let errors : Vec<InputError> = {
+            let net = &self.net;
+            inps.iter().filter_map(|&(pl_id, _)| input_error(net, pl_id)).collect()
+        };
+        let inps = if errors.is_empty() {
+            inps
+        } else {
+            self.stats.rejected_inputs += errors.len() as u64;
+            match self.settings.input_policy {
+                InputPolicy::Reject => return Err(ExecutionError::InvalidInput(errors[0].clone())),
+                InputPolicy::Ignore => {
+                    let net = &self.net;
+                    inps.into_iter().filter(|&(pl_id, _)| input_error(net, pl_id).is_none()).collect()
+                },
+            }
+        };
+        let mut checked = Vec::with_capacity(inps.len());
+        for (pl_id, tk) in inps.into_iter() {
+            if tk.is_nan() {
+                self.fault(ExecutionError::NaNToken(NaNSource::InputPlace(pl_id)))?;
+            } else {
+                checked.push((pl_id, tk));
+            }
+        }
+        Ok(checked)
+    }
+
+    /// Every delay is counted down even if a finishing transition aborts, the first fault is returned
+    pub fn update_delay_state(&mut self) -> Result<(), ExecutionError> {
+        self.stats.ticks += 1;
+        let mut fault = Ok(());
         for tr_id in 0..self.net.get_trans_nr() {
             if self.trans_state[tr_id] > 0 {
+                self.stats.transitions[tr_id].delay_ticks += 1;
                 if self.trans_state[tr_id] == 1{
-                    self.finish_fire(tr_id);
+                    let finished = self.finish_fire(tr_id);
+                    if fault.is_ok() {
+                        fault = finished;
+                    }
                 }
                 self.trans_state[tr_id] -= 1;
             }
         }
+        fault
     }
 
 
-    pub fn execute_firable_transitions(&mut self) {
+    pub fn execute_firable_transitions(&mut self) -> Result<Option<LoopLimitDiagnostic>, ExecutionError> {
         let mut loop_cntr= 0;
-        let max_loop = 40;
-        let mut heappened_something = true;
-        while heappened_something && loop_cntr < max_loop {
-            heappened_something = false;
+        while loop_cntr < self.settings.max_loop {
+            if self.fire_next()?.is_none() {
+                self.record_loop_iterations(loop_cntr);
+                return Ok(None);
+            }
             loop_cntr += 1;
-            let pos ={self.get_possible_executable_trans()};
-            for tr_id in pos{
-                match self.is_fireable(tr_id){
-                    None => {/*does notin*/},
-                    Some(inps) => {
-                        heappened_something = true;
-                        self.start_fire(tr_id, inps);
+        }
+        self.record_loop_iterations(loop_cntr);
+        Ok(self.loop_limit_reached())
+    }
+
+    pub fn record_loop_iterations(&mut self, iterations: usize) {
+        if iterations > self.stats.max_loop_iterations {
+            self.stats.max_loop_iterations = iterations;
+        }
+    }
+
+    /// Starts firing the transition the conflict policy chooses, None if nothing is fireable
+    pub fn fire_next(&mut self) -> Result<Option<usize>, ExecutionError> {
+        let pos ={self.get_possible_executable_trans()};
+        match self.choose_fireable(pos) {
+            Some((tr_id, inps)) => {
+                self.start_fire(tr_id, inps)?;
+                Ok(Some(tr_id))
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Counts the fault, it is an error only if the policy aborts
+    fn fault(&mut self, err: ExecutionError) -> Result<(), ExecutionError> {
+        self.stats.faults += 1;
+        match self.settings.fault_policy {
+            FaultPolicy::Abort => Err(err),
+            FaultPolicy::SkipTransition | FaultPolicy::NaNToPhi => Ok(()),
+        }
+    }
+
+    /// Called after `max_loop` firings, reports the transitions that are still fireable
+    pub fn loop_limit_reached(&mut self) -> Option<LoopLimitDiagnostic> {
+        let max_loop = self.settings.max_loop;
+        let fireable : Vec<usize> = self.get_possible_executable_trans().into_iter()
+            .filter(|tr_id| self.is_fireable(*tr_id).is_some())
+            .collect();
+        if fireable.is_empty() {
+            return None;
+        }
+        let diag = LoopLimitDiagnostic {
+            max_loop: max_loop,
+            cycle: zero_delay_cycle(&self.net, &fireable),
+            fireable: fireable,
+        };
+        self.stats.loop_limit_hits += 1;
+        if let Some(ref mut handler) = self.loop_limit_handler {
+            handler.loop_limit_hit(&diag);
+        }
+        Some(diag)
+    }
+
+    pub fn get_marking(&self) -> &Vec<UnifiedToken> {
+        &self.place_state
+    }
+
+    /// A new conflict policy starts over, the previous winner and the random state are dropped
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        let policy = settings.conflict_policy;
+        self.settings = settings;
+        self.last_winner = None;
+        self.rng_state = match policy {
+            // xorshift never leaves zero
+            ConflictPolicy::SeededRandom(seed) => if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+            _ => 0,
+        };
+        if let Some(ref mut trace) = self.trace {
+            trace.set_policy(&policy.name());
+        }
+    }
+
+    /// Swaps the table of a transition on the fly, the marking and the delays are kept.
+    /// The executor gets its own copy of the net if it shares it, the cache of possibly
+    /// executable transitions starts over as it depends on the tables
+    pub fn replace_table(&mut self, tr_id: usize, table: UnifiedTableE) -> Result<(), HotSwapError> {
+        if tr_id >= self.net.get_trans_nr() {
+            return Err(HotSwapError::NoSuchTransition(tr_id));
+        }
+        Arc::make_mut(&mut self.net).replace_table(tr_id, table)
+            .map_err(HotSwapError::TableDoesNotFit)?;
+        self.cached_possibly_exec = Arc::new(RwLock::new(HashMap::default()));
+        Ok(())
+    }
+
+    pub fn set_place_scale(&mut self, pl_id: usize, scale: f32) -> Result<(), HotSwapError> {
+        if pl_id >= self.net.get_place_nr() {
+            return Err(HotSwapError::NoSuchPlace(pl_id));
+        }
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(HotSwapError::WrongScale(scale));
+        }
+        Arc::make_mut(&mut self.net).set_place_scale(pl_id, scale);
+        Arc::make_mut(&mut self.scales)[pl_id] = TriangleFuzzyfier::with_min_max(-1.0*scale, scale);
+        Ok(())
+    }
+
+    pub fn set_trace(&mut self, mut recorder: TraceRecorder) {
+        recorder.set_policy(&self.settings.conflict_policy.name());
+        self.trace = Some(recorder);
+    }
+
+    /// The transition to fire next out of `pos`, which is in the executor's order.
+    /// Without tracing the static policy takes the first fireable one, as it always did.
+    /// Refusals are counted only up to the first fireable transition, so the statistics
+    /// do not depend on whether the rest had to be checked.
+    fn choose_fireable(&mut self, pos: Vec<usize>) -> Option<(usize, Vec<UnifiedToken>)> {
+        let first_is_enough = self.settings.conflict_policy == ConflictPolicy::StaticPriority && self.trace.is_none();
+        let mut candidates : Vec<(usize, Vec<UnifiedToken>)> = vec![];
+        for tr_id in pos {
+            match self.is_fireable(tr_id) {
+                Some(inps) => {
+                    candidates.push((tr_id, inps));
+                    if first_is_enough {
                         break;
                     }
+                },
+                None => if candidates.is_empty() {
+                    self.count_refusal(tr_id);
+                },
+            }
+        }
+        if first_is_enough || candidates.is_empty() {
+            return candidates.pop();
+        }
+        // the first one and those competing with it for an input place, the rest waits its turn
+        let first = candidates[0].0;
+        let net = self.net.clone();
+        let contested = net.get_places_befor_trans(first);
+        candidates.retain(|&(tr_id, _)|
+            tr_id == first || net.get_places_befor_trans(tr_id).iter().any(|pl| contested.contains(pl)));
+        let top = candidates.iter().map(|&(tr_id, _)| net.get_priority(tr_id)).max().expect("not empty");
+        candidates.retain(|&(tr_id, _)| net.get_priority(tr_id) == top);
+
+        let chosen = match self.settings.conflict_policy {
+            ConflictPolicy::StaticPriority => 0,
+            ConflictPolicy::RoundRobin => {
+                let after = self.last_winner.map_or(0, |last| self.order_pos[last] + 1);
+                candidates.iter()
+                    .position(|&(tr_id, _)| self.order_pos[tr_id] >= after)
+                    .unwrap_or(0)
+            },
+            ConflictPolicy::SeededRandom(_) => (self.next_random() % candidates.len() as u64) as usize,
+            ConflictPolicy::HighestActivation => {
+                let mut best = 0;
+                let mut best_activation = -1.0;
+                for (i, &(tr_id, ref inps)) in candidates.iter().enumerate() {
+                    let activation = self.activation(tr_id, inps);
+                    if activation > best_activation {
+                        best = i;
+                        best_activation = activation;
+                    }
                 }
+                best
+            },
+        };
+        let winner = candidates.swap_remove(chosen);
+        if candidates.is_empty() {
+            return Some(winner);
+        }
+        // only conflicts move the round robin on, a lone fireable transition is no turn
+        self.last_winner = Some(winner.0);
+        if self.trace.is_some() {
+            let mut all : Vec<usize> = candidates.iter().map(|&(tr_id, _)| tr_id).collect();
+            all.push(winner.0);
+            all.sort_by_key(|tr_id| self.order_pos[*tr_id]);
+            if let Some(ref mut trace) = self.trace {
+                trace.record_conflict(all, winner.0);
             }
         }
+        Some(winner)
+    }
+
+    pub fn activation(&self, tr_id: usize, inps: &Vec<UnifiedToken>) -> f32 {
+        if inps.len() != self.net.typed_table_for_trans(tr_id).input_nr() {
+            return 0.0;
+        }
+        self.net.table_for_trans(tr_id).activation(inps, &self.get_inp_scales(tr_id))
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        x
+    }
+
+    pub fn marked_places<'a>(&'a self) -> impl Iterator<Item=(usize, &'a UnifiedToken)> + 'a {
+        self.place_state.iter().enumerate().filter(|&(_, tk)| tk.not_phi())
+    }
+
+    pub fn take_snapshot(&self) -> ExecutorSnapshot {
+        ExecutorSnapshot {
+            place_state: self.place_state.clone(),
+            place_arrivals: self.place_arrivals.clone(),
+            trans_state: self.trans_state.clone(),
+            trans_holds: self.trans_holds.clone(),
+            last_winner: self.last_winner,
+            rng_state: self.rng_state,
+        }
+    }
+
+    /// Fails without changing the state if the snapshot does not fit the net
+    pub fn load_snapshot(&mut self, snap: ExecutorSnapshot) -> Result<(), SnapshotError> {
+        snap.check_shape(&self.net)?;
+        self.place_state = snap.place_state;
+        self.place_arrivals = snap.place_arrivals;
+        self.trans_state = snap.trans_state;
+        self.trans_holds = snap.trans_holds;
+        self.last_winner = snap.last_winner;
+        if snap.rng_state != 0 {
+            self.rng_state = snap.rng_state;
+        }
+        Ok(())
+    }
+
+    /// Back to the initial marking with no transition firing, the statistics are kept
+    pub fn reset(&mut self) {
+        self.place_state = init_place_state(&self.net);
+        self.place_arrivals = init_place_arrivals(&self.net);
+        self.trans_state = vec![0; self.net.get_trans_nr()];
+        self.trans_holds = vec![vec![]; self.net.get_trans_nr()];
+        self.outputs.clear();
+    }
+
+    /// Fault injection: merges the token into the place, which does not have to be an input place,
+    /// and fires what became fireable
+    pub fn inject_token(&mut self, pl_id: usize, tk: UnifiedToken) -> Result<(), ExecutionError> {
+        if pl_id >= self.net.get_place_nr() {
+            return Err(ExecutionError::InvalidInput(InputError::NoSuchPlace(pl_id)));
+        }
+        if tk.is_nan() {
+            return self.fault(ExecutionError::NaNToken(NaNSource::InputPlace(pl_id)));
+        }
+        if let Some(ref mut trace) = self.trace {
+            trace.record_inputs(&vec![(pl_id, tk.clone())]);
+        }
+        self.merge_into(pl_id, tk);
+        let fired = self.execute_firable_transitions();
+        self.end_step(false);
+        fired.map(|_| ())
+    }
 
+    /// Handles one message of the asynchronous executors, returns false on `Stop`
+    fn handle_msg(&mut self, msg: AsyncExecutorMsg) -> bool {
+        match msg {
+           AsyncExecutorMsg::Tick => {
+               if self.paused {
+                   self.stats.paused_ticks += 1;
+                   return true;
+               }
+               let started = Instant::now();
+               self.outputs.clear();
+               // there is no one to answer, faults show up in the statistics
+               let _ = self.update_delay_state().and_then(|_| self.execute_firable_transitions());
+               self.end_step(true);
+               self.stats.add_tick_time(started.elapsed());
+           },
+           AsyncExecutorMsg::Input(v) => {
+               self.outputs.clear();
+               // there is no one to answer, rejected inputs and faults show up in the statistics
+               if self.put_tokens_to_inp_places(v).is_ok() {
+                   let _ = self.execute_firable_transitions();
+                   self.end_step(false);
+               }
+           },
+           AsyncExecutorMsg::TakeSnapshot(reply) => {
+               let _ = reply.send(self.take_snapshot());
+           },
+           AsyncExecutorMsg::LoadSnapshot(snap, reply) => {
+               let _ = reply.send(self.load_snapshot(snap));
+           },
+           AsyncExecutorMsg::GetMarking(reply) => {
+               let _ = reply.send(self.place_state.clone());
+           },
+           AsyncExecutorMsg::GetStatistics(reply) => {
+               let _ = reply.send(self.stats.clone());
+           },
+           AsyncExecutorMsg::Reset(reply) => {
+               self.reset();
+               let _ = reply.send(());
+           },
+           AsyncExecutorMsg::InjectToken(pl_id, tk, reply) => {
+               self.outputs.clear();
+               let _ = reply.send(self.inject_token(pl_id, tk));
+           },
+           AsyncExecutorMsg::ReplaceTable(tr_id, table, reply) => {
+               let _ = reply.send(self.replace_table(tr_id, table));
+           },
+           AsyncExecutorMsg::SetPlaceScale(pl_id, scale, reply) => {
+               let _ = reply.send(self.set_place_scale(pl_id, scale));
+           },
+           AsyncExecutorMsg::Pause(reply) => {
+               self.paused = true;
+               let _ = reply.send(());
+           },
+           AsyncExecutorMsg::Resume(reply) => {
+               self.paused = false;
+               let _ = reply.send(());
+           },
+           AsyncExecutorMsg::Stop => return false,
+        }
+        true
     }
 
-    fn finish_fire(&mut self, tr_id: usize) {
+    /// A rejected tick does not happen, the delays are not advanced.
+    /// An aborted tick is still traced, its outputs are lost.
+    pub fn run_tick(&mut self, inps: Vec<(usize, UnifiedToken)>) -> Result<TickResult, ExecutionError> {
+        let started = Instant::now();
+        self.outputs.clear();
+        self.put_tokens_to_inp_places(inps)?;
+        let fired = self.update_delay_state().and_then(|_| self.execute_firable_transitions());
+        self.end_step(true);
+        self.stats.add_tick_time(started.elapsed());
+        fired?;
+        Ok(TickResult {
+            outputs: mem::replace(&mut self.outputs, vec![]),
+        })
+    }
+
+    /// closes the current trace step, if tracing is on
+    pub fn end_step(&mut self, tick_ended: bool) {
+        if let Some(ref mut trace) = self.trace {
+            trace.end_step(&self.place_state, &self.trans_holds, &self.trans_state, tick_ended);
+        }
+    }
+
+    fn finish_fire(&mut self, tr_id: usize) -> Result<(), ExecutionError> {
         let mut rez = mem::replace(&mut self.trans_holds[tr_id], vec![]);
+        if let Some(ref mut trace) = self.trace {
+            trace.record_finish(tr_id, rez.clone());
+        }
+        let out_places = self.net.get_places_after_trans(tr_id).clone();
+        let expected = if self.net.is_trans_out(tr_id) { 1 } else { out_places.len() };
+        if rez.len() != expected {
+            return self.fault(ExecutionError::ArityMismatch{tr_id: tr_id, expected: expected, found: rez.len()});
+        }
         if self.net.is_trans_out(tr_id) {
+            self.stats.output_tokens += 1;
+            self.outputs.push((tr_id, rez[0].clone()));
             self.event_manager.execute_handler(tr_id,
                                                mem::replace(&mut rez[0], UnifiedToken::Phi));
         } else {
-            let out_places = self.net.get_places_after_trans(tr_id);
-            if rez.len() != out_places.len() {
-                panic!("wrong number of ouputs fot tr{},
-                       it has {} from table but {} out places",
-                       tr_id, rez.len(), out_places.len());
-            }
             for i in 0..rez.len() {
-                 self.place_state[out_places[i]].unite(
-                     mem::replace(&mut rez[i], UnifiedToken::Phi));
+                 self.merge_into(out_places[i], mem::replace(&mut rez[i], UnifiedToken::Phi));
             }
         }
-
-
+        Ok(())
     }
 
-    fn start_fire(&mut self, tr_id: usize, inp_tokens: Vec<UnifiedToken>) {
+    /// The inputs are consumed even if the firing is faulty
+    fn start_fire(&mut self, tr_id: usize, inp_tokens: Vec<UnifiedToken>) -> Result<(), ExecutionError> {
 
+        self.stats.firings += 1;
+        self.stats.transitions[tr_id].firings += 1;
         self.clear_inp_tokens(tr_id);
-        let rez ;
+        let expected = self.net.typed_table_for_trans(tr_id).input_nr();
+        if inp_tokens.len() != expected {
+            return self.fault(ExecutionError::ArityMismatch{tr_id: tr_id, expected: expected, found: inp_tokens.len()});
+        }
+        let delay = self.calc_delay(tr_id, &inp_tokens);
+        let inp_tokens_copy = if self.trace.is_some() { inp_tokens.clone() } else { vec![] };
+        let mut rez ;
         {
             let defuzz = self.get_out_scales(tr_id);
             let fuzz = self.get_inp_scales(tr_id);
             rez = self.net.table_for_trans(tr_id).execute(inp_tokens, &fuzz, &defuzz) ;
         }
+        if rez.iter().any(|tk| tk.is_nan()) {
+            self.fault(ExecutionError::NaNToken(NaNSource::Transition(tr_id)))?;
+            if self.settings.fault_policy != FaultPolicy::NaNToPhi {
+                return Ok(());
+            }
+            rez = rez.into_iter().map(|tk| if tk.is_nan() { UnifiedToken::Phi } else { tk }).collect();
+        }
+        if let Some(ref mut trace) = self.trace {
+            trace.record_start(FiringStart{
+                tr_id: tr_id, inputs: inp_tokens_copy, outputs: rez.clone(), delay: delay});
+        }
         self.trans_holds[tr_id] = rez;
-        let delay = self.net.get_delay(tr_id);
-        if delay == 0 {
-            self.finish_fire(tr_id);
+        if delay <= 0 {
+            self.finish_fire(tr_id)
         } else {
             self.trans_state[tr_id] = delay;
+            Ok(())
+        }
+    }
+
+
+    /// delay = base + multiplier * value of the first non phi input token, rounded to ticks
+    fn calc_delay(&self, tr_id: usize, inp_tokens: &Vec<UnifiedToken>) -> i32 {
+        let delay = self.net.get_delay(tr_id);
+        let multiplier = self.net.get_delay_multiplier(tr_id);
+        if multiplier == 0.0 {
+            return delay;
         }
+        let value = inp_tokens.iter()
+            .filter_map(|tk| tk.as_option())
+            .next()
+            .unwrap_or(0.0);
+        delay + (multiplier * value).round() as i32
     }
 
+    fn merge_into(&mut self, pl_id: usize, tk: UnifiedToken) {
+        let strategy = self.net.get_merge_strategy(pl_id);
+        self.place_state[pl_id].merge(tk, strategy, &mut self.place_arrivals[pl_id]);
+    }
 
     fn get_inp_token(&self, tr_id: usize) -> Vec<UnifiedToken> {
         let mut to_ret = vec![];
         let inp_places = self.net.get_places_befor_trans(tr_id);
-        for place in inp_places {
-            let ft = self.place_state[*place].clone();
+        let weights = self.net.get_weights_befor_trans(tr_id);
+        for (place, weight) in inp_places.iter().zip(weights) {
+            let ft = self.place_state[*place].weighted(*weight);
             to_ret.push(ft);
         }
         to_ret
@@ -143,11 +800,22 @@ impl BasicUnifiedPetriExecutor {
         }
     }
 
+    /// Counts that the table did not fire, a busy transition is not refused
+    fn count_refusal(&mut self, tr_id: usize) {
+        if self.trans_state[tr_id] == 0 {
+            self.stats.transitions[tr_id].not_fireable += 1;
+        }
+    }
+
     fn is_fireable(&self, tr_id: usize) -> Option<Vec<UnifiedToken>>{
         if self.trans_state[tr_id] != 0{
             return None;
         }
         let inp_tokens = self.get_inp_token(tr_id);
+        if inp_tokens.len() != self.net.typed_table_for_trans(tr_id).input_nr() {
+            // the table would index out of its inputs, a marked one lets start_fire report the fault
+            return if inp_tokens.iter().any(|tk| tk.not_phi()) { Some(inp_tokens) } else { None };
+        }
         let fuzz = self.get_inp_scales(tr_id);
         if self.net.table_for_trans(tr_id).is_executable(&inp_tokens, &fuzz) {
             Some(inp_tokens)
@@ -177,30 +845,59 @@ impl BasicUnifiedPetriExecutor {
     }
     fn get_possible_executable_trans(&mut self) -> Vec<usize>{
         let sm = self.simplyfied_marking();
-        if !self.cached_possibly_exec.contains_key(&sm) {
-            let v = self.possibly_executable_trans(&sm);
-            self.cached_possibly_exec.insert(sm.clone(), v);
+        if let Some(v) = self.cached_possibly_exec.read().unwrap().get(&sm) {
+            self.stats.cache_hits += 1;
+            return v.clone();
         }
-        return self.cached_possibly_exec.get(&sm).unwrap().clone()
-
+        self.stats.cache_misses += 1;
+        let v = possibly_executable_trans(&*self.net, &self.trans_order, &sm);
+        self.cached_possibly_exec.write().unwrap().insert(sm, v.clone());
+        v
     }
 
     fn simplyfied_marking(&self) -> Vec<bool>{
         self.place_state.iter().map(|x| x.not_phi()).collect()
     }
 
-    fn possibly_executable_trans(&self, simpl_mark : &Vec<bool>) -> Vec<usize> {
-        self.trans_order.iter()
-            .map(|x|  (x,self.net.get_places_befor_trans(*x).iter().map(|pl| simpl_mark[*pl])
-                       .collect::<Vec<bool>>()))
-            .filter(|&(ref tr_nr, ref inp)|
-                    self.net.table_for_trans(**tr_nr).possibly_executable(&inp))
-            .map(|(tr_nr, _)| (*tr_nr).clone())
-            .collect()
-    }
 
 }
 
+/// Shortest cycle through zero delay transitions that contains one of `from`, empty if there is none
+fn zero_delay_cycle(net: &UnifiedPetriNet, from: &Vec<usize>) -> Vec<usize> {
+    for start in from {
+        if net.has_delay(*start) {
+            continue;
+        }
+        let mut parent : HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(*start);
+        while let Some(tr_id) = queue.pop_front() {
+            for pl_id in net.get_places_after_trans(tr_id) {
+                for next in net.get_tanss_after_place(*pl_id) {
+                    if net.has_delay(*next) {
+                        continue;
+                    }
+                    if *next == *start {
+                        let mut cycle = vec![tr_id];
+                        let mut current = tr_id;
+                        while current != *start {
+                            current = parent[&current];
+                            cycle.push(current);
+                        }
+                        cycle.reverse();
+                        return cycle;
+                    }
+                    if !parent.contains_key(next) {
+                        parent.insert(*next, tr_id);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+    }
+    vec![]
+}
+
 fn init_scales(net: &UnifiedPetriNet) ->Vec<TriangleFuzzyfier> {
     let mut to_ret =vec![];
     for place_id in 0..net.get_place_nr() {
@@ -217,41 +914,21 @@ fn init_place_state(net: &UnifiedPetriNet) -> Vec<UnifiedToken>{
     to_ret
 }
 
-fn order_of_transitions(net: &UnifiedPetriNet) -> Vec<usize> {
-    let mut inp_trs  = vec![];
-    let mut out_trs  = vec![];
-    let mut nondelay_trs = vec![];
-    let mut delays_trs = vec![];
-    for tr_id in 0..net.get_trans_nr() {
-        let places_needed = net.get_places_befor_trans(tr_id);
-        let mut found = false ;
-        for pl_id in places_needed {
-            if net.is_place_inp(*pl_id) {
-                inp_trs.push(tr_id);
-                found = true;
-                break;
-            }
-        }
-        if ! found {
-            if net.is_trans_out(tr_id) {
-                out_trs.push(tr_id);
-                continue;
-            }
-
-            if net.get_delay(tr_id) == 0{
-                nondelay_trs.push(tr_id);
-            } else {
-                delays_trs.push(tr_id);
-
-            }
-        }
+fn input_error(net: &UnifiedPetriNet, pl_id: usize) -> Option<InputError> {
+    if pl_id >= net.get_place_nr() {
+        Some(InputError::NoSuchPlace(pl_id))
+    } else if !net.is_place_inp(pl_id) {
+        Some(InputError::NotAnInputPlace(pl_id))
+    } else {
+        None
     }
-    inp_trs.append(&mut out_trs);
-    inp_trs.append(&mut nondelay_trs);
-    inp_trs.append(&mut delays_trs);
-    inp_trs
 }
 
+fn init_place_arrivals(net: &UnifiedPetriNet) -> Vec<u32> {
+    (0..net.get_place_nr())
+        .map(|place_id| if net.get_initial_marking(place_id).not_phi() { 1 } else { 0 })
+        .collect()
+}
 
 pub struct SynchronousUnifiedPetriExecutor{
     basic : BasicUnifiedPetriExecutor,
@@ -264,18 +941,474 @@ impl SynchronousUnifiedPetriExecutor {
         }
     }
 
-    pub fn run_tick(&mut self, inps: Vec<(usize, UnifiedToken)>) {
-        self.basic.put_tokens_to_inp_places(inps);
-        self.basic.update_delay_state();
-        self.basic.execute_firable_transitions();
+    /// The registered consumers are still called, the result lists the same tokens.
+    /// Tokens for places that are not input places are handled by the input policy,
+    /// faulty firings by the fault policy.
+    pub fn run_tick(&mut self, inps: Vec<(usize, UnifiedToken)>) -> Result<TickResult, ExecutionError> {
+        self.basic.run_tick(inps)
+    }
+
+    pub fn settings(&self) -> &ExecutorSettings {
+        &self.basic.settings
+    }
+
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        self.basic.set_settings(settings);
+    }
+
+    pub fn set_loop_limit_handler(&mut self, handler: Box<LoopLimitHandler>) {
+        self.basic.loop_limit_handler = Some(handler);
+    }
+
+    pub fn replace_table(&mut self, tr_id: usize, table: UnifiedTableE) -> Result<(), HotSwapError> {
+        self.basic.replace_table(tr_id, table)
+    }
+
+    pub fn set_place_scale(&mut self, pl_id: usize, scale: f32) -> Result<(), HotSwapError> {
+        self.basic.set_place_scale(pl_id, scale)
+    }
+
+    pub fn set_trace_recorder(&mut self, recorder: TraceRecorder) {
+        self.basic.set_trace(recorder);
+    }
+
+    /// The ticks recorded in memory since the last call
+    pub fn take_trace(&mut self) -> Vec<TickTrace> {
+        self.basic.trace.as_mut().map_or(vec![], |trace| trace.take())
+    }
+
+    pub fn take_snapshot(&self) -> ExecutorSnapshot {
+        self.basic.take_snapshot()
+    }
+
+    pub fn load_snapshot(&mut self, snap: ExecutorSnapshot) -> Result<(), SnapshotError> {
+        self.basic.load_snapshot(snap)
+    }
+
+    pub fn get_net(&self) -> &UnifiedPetriNet {
+        &self.basic.net
+    }
+
+    pub fn get_marking(&self) -> &Vec<UnifiedToken> {
+        self.basic.get_marking()
+    }
+
+    pub fn get_place_token(&self, pl_id: usize) -> &UnifiedToken {
+        &self.basic.place_state[pl_id]
+    }
+
+    /// The places holding a token, with their token
+    pub fn marked_places<'a>(&'a self) -> impl Iterator<Item=(usize, &'a UnifiedToken)> + 'a {
+        self.basic.marked_places()
+    }
+
+    /// Ticks left until the transition puts its held tokens out, 0 if it is not firing
+    pub fn get_remaining_delay(&self, tr_id: usize) -> i32 {
+        self.basic.trans_state[tr_id]
+    }
+
+    /// The output tokens of a delayed transition, empty if it is not firing
+    pub fn get_held_tokens(&self, tr_id: usize) -> &Vec<UnifiedToken> {
+        &self.basic.trans_holds[tr_id]
+    }
+
+    pub fn get_statistics(&self) -> &ExecutorStatistics {
+        &self.basic.stats
+    }
+
+    /// Back to the initial marking with no transition firing, the statistics are kept
+    pub fn reset(&mut self) {
+        self.basic.reset();
+    }
+
+    /// Fault injection: puts a token into any place, not only an input place,
+    /// and fires what became fireable, without a tick
+    pub fn inject_token(&mut self, pl_id: usize, tk: UnifiedToken) -> Result<(), ExecutionError> {
+        self.basic.inject_token(pl_id, tk)
+    }
+}
+
+/// Runs many independent instances of one net in lock step.
+/// The instances share the net and its caches, each has its own marking and delays.
+/// There are no consumers, the outputs come back in the tick results.
+pub struct BatchUnifiedPetriExecutor {
+    instances: Vec<BasicUnifiedPetriExecutor>,
+    threads: usize,
+}
+
+impl BatchUnifiedPetriExecutor {
+    pub fn new(net: UnifiedPetriNet, instance_nr: usize) -> BatchUnifiedPetriExecutor {
+        assert!(instance_nr > 0, "a batch needs at least one instance");
+        let first = BasicUnifiedPetriExecutor::new(net, EventManager::new());
+        let mut instances : Vec<BasicUnifiedPetriExecutor> = (1..instance_nr)
+            .map(|_| first.new_instance(EventManager::new()))
+            .collect();
+        instances.insert(0, first);
+        BatchUnifiedPetriExecutor {
+            instances: instances,
+            threads: 1,
+        }
+    }
+
+    pub fn instance_nr(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// The instances are split into this many chunks, each stepped on its own thread, 1 steps them in place
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = if threads == 0 { 1 } else { threads };
+    }
+
+    /// One thread per available core
+    pub fn use_all_cores(&mut self) {
+        let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        self.set_threads(cores);
+    }
+
+    /// The instances always share the settings
+    pub fn settings(&self) -> &ExecutorSettings {
+        &self.instances[0].settings
+    }
+
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        for instance in self.instances.iter_mut() {
+            instance.set_settings(settings);
+        }
+    }
+
+    /// `inps[i]` goes to instance i, the i-th result is what instance i produced,
+    /// an instance whose inputs are rejected skips the tick
+    pub fn run_tick(&mut self, inps: Vec<Vec<(usize, UnifiedToken)>>) -> Vec<Result<TickResult, ExecutionError>> {
+        assert_eq!(inps.len(), self.instances.len(), "one input batch is needed for every instance");
+        if self.threads <= 1 || self.instances.len() <= 1 {
+            return self.instances.iter_mut().zip(inps)
+                .map(|(instance, inp)| instance.run_tick(inp))
+                .collect();
+        }
+        let chunk = (self.instances.len() + self.threads - 1) / self.threads;
+        let mut inps = inps.into_iter();
+        thread::scope(|scope| {
+            let handles : Vec<_> = self.instances.chunks_mut(chunk)
+                .map(|instances| {
+                    let inps : Vec<Vec<(usize, UnifiedToken)>> = inps.by_ref().take(instances.len()).collect();
+                    scope.spawn(move || {
+                        instances.iter_mut().zip(inps)
+                            .map(|(instance, inp)| instance.run_tick(inp))
+                            .collect::<Vec<Result<TickResult, ExecutionError>>>()
+                    })
+                })
+                .collect();
+            handles.into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    pub fn get_net(&self) -> &UnifiedPetriNet {
+        &self.instances[0].net
+    }
+
+    pub fn get_marking(&self, instance: usize) -> &Vec<UnifiedToken> {
+        self.instances[instance].get_marking()
+    }
+
+    pub fn get_statistics(&self, instance: usize) -> &ExecutorStatistics {
+        &self.instances[instance].stats
+    }
+
+    pub fn take_snapshot(&self, instance: usize) -> ExecutorSnapshot {
+        self.instances[instance].take_snapshot()
+    }
+
+    /// Fails without changing the instance if the snapshot does not fit the net
+    pub fn load_snapshot(&mut self, instance: usize, snap: ExecutorSnapshot) -> Result<(), SnapshotError> {
+        self.instances[instance].load_snapshot(snap)
+    }
+
+    pub fn reset(&mut self, instance: usize) {
+        self.instances[instance].reset();
+    }
+}
+
+/// Where the debugger stops, see `UnifiedPetriDebugger::add_breakpoint`
+pub enum Breakpoint {
+    /// the transition starts firing
+    Transition(usize),
+    /// the place gets a token after being phi
+    PlaceMarked(usize),
+    /// the condition on the value of the place's token turns true
+    TokenValue(usize, Box<Fn(f32) -> bool + Send>),
+}
+
+/// Why a transition fired: its input tokens, how strongly they fired its table
+/// and the transitions it won against, empty if there was no conflict
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringReport {
+    pub start: FiringStart,
+    pub activation: f32,
+    pub competing: Vec<usize>,
+    /// a transition without delay finishes in the same step
+    pub finished: Vec<FiringFinish>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepEvent {
+    /// the inputs are in their places and the delays counted down, `finished` lists the delayed
+    /// transitions that put their tokens out
+    TickStarted{tick: u64, inputs: Vec<(usize, UnifiedToken)>, finished: Vec<FiringFinish>},
+    Fired(FiringReport),
+    /// nothing is fireable any more, or the loop limit stopped the tick
+    TickEnded{tick: u64, outputs: Vec<(usize, UnifiedToken)>, loop_limit: Option<LoopLimitDiagnostic>},
+    /// the fault policy stopped the tick, its outputs are lost
+    Aborted{tick: u64, error: ExecutionError},
+}
+
+/// What one step did and the ids of the breakpoints it hit
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugStep {
+    pub event: StepEvent,
+    pub hits: Vec<usize>,
+}
+
+/// Executes a tick the way the synchronous executor does, but one firing per step.
+/// Every step is traced in memory, `take_trace` empties it.
+pub struct UnifiedPetriDebugger {
+    basic: BasicUnifiedPetriExecutor,
+    breakpoints: Vec<(usize, Breakpoint)>,
+    next_breakpoint: usize,
+    inputs: Vec<(usize, UnifiedToken)>,
+    in_tick: bool,
+    loop_cntr: usize,
+    /// a delayed transition that aborted while the tick started, reported by the next step
+    fault: Option<ExecutionError>,
+    /// spent in the steps of the current tick
+    tick_time: time::Duration,
+}
+
+impl UnifiedPetriDebugger {
+    pub fn new(net: UnifiedPetriNet, men: EventManager) -> UnifiedPetriDebugger {
+        let mut basic = BasicUnifiedPetriExecutor::new(net, men);
+        basic.set_trace(TraceRecorder::in_memory());
+        UnifiedPetriDebugger {
+            basic: basic,
+            breakpoints: vec![],
+            next_breakpoint: 0,
+            inputs: vec![],
+            in_tick: false,
+            loop_cntr: 0,
+            fault: None,
+            tick_time: time::Duration::from_secs(0),
+        }
+    }
+
+    pub fn settings(&self) -> &ExecutorSettings {
+        &self.basic.settings
+    }
+
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        self.basic.set_settings(settings);
+    }
+
+    /// Returns the id the hits report it with
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        let id = self.next_breakpoint;
+        self.next_breakpoint += 1;
+        self.breakpoints.push((id, breakpoint));
+        id
+    }
+
+    pub fn remove_breakpoint(&mut self, id: usize) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|&(bp_id, _)| bp_id != id);
+        before != self.breakpoints.len()
+    }
+
+    /// The tokens are put to their places when the next tick starts,
+    /// the input and the fault policies are applied right away
+    pub fn add_inputs(&mut self, inps: Vec<(usize, UnifiedToken)>) -> Result<(), ExecutionError> {
+        let inps = self.basic.check_inputs(inps)?;
+        self.inputs.extend(inps);
+        Ok(())
+    }
+
+    pub fn is_in_tick(&self) -> bool {
+        self.in_tick
+    }
+
+    /// Starts a tick, fires one transition or ends the tick
+    pub fn step(&mut self) -> DebugStep {
+        let before = self.basic.place_state.clone();
+        let started = Instant::now();
+        let event = if self.in_tick { self.fire_or_end() } else { self.start_tick() };
+        self.tick_time += started.elapsed();
+        if !self.in_tick {
+            let tick_time = mem::replace(&mut self.tick_time, time::Duration::from_secs(0));
+            self.basic.stats.add_tick_time(tick_time);
+        }
+        let hits = self.hits(&before, &event);
+        DebugStep { event: event, hits: hits }
+    }
+
+    /// Steps until a breakpoint is hit, None if none is hit in `max_steps` steps
+    pub fn run_to_breakpoint(&mut self, max_steps: usize) -> Option<DebugStep> {
+        for _ in 0..max_steps {
+            let step = self.step();
+            if !step.hits.is_empty() {
+                return Some(step);
+            }
+        }
+        None
+    }
+
+    /// Steps to the end of the current tick, or of the next one between ticks, ignoring breakpoints
+    pub fn finish_tick(&mut self) -> Vec<DebugStep> {
+        let mut steps = vec![self.step()];
+        while self.in_tick {
+            steps.push(self.step());
+        }
+        steps
+    }
+
+    pub fn get_net(&self) -> &UnifiedPetriNet {
+        &self.basic.net
+    }
+
+    pub fn get_marking(&self) -> &Vec<UnifiedToken> {
+        self.basic.get_marking()
+    }
+
+    pub fn get_remaining_delay(&self, tr_id: usize) -> i32 {
+        self.basic.trans_state[tr_id]
+    }
+
+    pub fn take_trace(&mut self) -> Vec<TickTrace> {
+        self.basic.trace.as_mut().map_or(vec![], |trace| trace.take())
+    }
+
+    pub fn get_statistics(&self) -> &ExecutorStatistics {
+        &self.basic.stats
+    }
+
+    fn current_trace(&self) -> &TickTrace {
+        self.basic.trace.as_ref().expect("the debugger always traces").current()
+    }
+
+    fn start_tick(&mut self) -> StepEvent {
+        let inputs = mem::replace(&mut self.inputs, vec![]);
+        let finished_before = self.current_trace().finished.len();
+        self.basic.outputs.clear();
+        // add_inputs already checked them
+        let _ = self.basic.put_tokens_to_inp_places(inputs.clone());
+        self.fault = self.basic.update_delay_state().err();
+        self.in_tick = true;
+        self.loop_cntr = 0;
+        let current = self.current_trace();
+        StepEvent::TickStarted {
+            tick: current.tick,
+            inputs: inputs,
+            finished: current.finished[finished_before..].to_vec(),
+        }
+    }
+
+    fn fire_or_end(&mut self) -> StepEvent {
+        if let Some(err) = self.fault.take() {
+            return self.abort_tick(err);
+        }
+        if self.loop_cntr >= self.basic.settings.max_loop {
+            self.basic.record_loop_iterations(self.loop_cntr);
+            let diag = self.basic.loop_limit_reached();
+            return self.end_tick(diag);
+        }
+        let (finished_before, conflicts_before) = {
+            let current = self.current_trace();
+            (current.finished.len(), current.conflicts.len())
+        };
+        let tr_id = match self.basic.fire_next() {
+            Ok(Some(tr_id)) => tr_id,
+            Ok(None) => {
+                self.basic.record_loop_iterations(self.loop_cntr);
+                return self.end_tick(None);
+            },
+            Err(err) => return self.abort_tick(err),
+        };
+        self.loop_cntr += 1;
+        let current = self.current_trace();
+        let start = current.started.last().expect("the firing was traced").clone();
+        let competing = current.conflicts[conflicts_before..].iter()
+            .flat_map(|conflict| conflict.candidates.iter().cloned())
+            .filter(|candidate| *candidate != tr_id)
+            .collect();
+        let finished = current.finished[finished_before..].to_vec();
+        StepEvent::Fired(FiringReport {
+            activation: self.basic.activation(tr_id, &start.inputs),
+            start: start,
+            competing: competing,
+            finished: finished,
+        })
+    }
+
+    fn end_tick(&mut self, loop_limit: Option<LoopLimitDiagnostic>) -> StepEvent {
+        let tick = self.current_trace().tick;
+        self.basic.end_step(true);
+        self.in_tick = false;
+        StepEvent::TickEnded {
+            tick: tick,
+            outputs: mem::replace(&mut self.basic.outputs, vec![]),
+            loop_limit: loop_limit,
+        }
+    }
+
+    fn abort_tick(&mut self, error: ExecutionError) -> StepEvent {
+        let tick = self.current_trace().tick;
+        self.basic.end_step(true);
+        self.basic.outputs.clear();
+        self.in_tick = false;
+        StepEvent::Aborted { tick: tick, error: error }
+    }
+
+    fn hits(&self, before: &Vec<UnifiedToken>, event: &StepEvent) -> Vec<usize> {
+        let after = &self.basic.place_state;
+        let holds = |tk: &UnifiedToken, cond: &Box<Fn(f32) -> bool + Send>| tk.as_option().map_or(false, |v| cond(v));
+        self.breakpoints.iter()
+            .filter(|&&(_, ref breakpoint)| match *breakpoint {
+                Breakpoint::Transition(tr_id) => match *event {
+                    StepEvent::Fired(ref report) => report.start.tr_id == tr_id,
+                    _ => false,
+                },
+                Breakpoint::PlaceMarked(pl_id) => !before[pl_id].not_phi() && after[pl_id].not_phi(),
+                Breakpoint::TokenValue(pl_id, ref cond) => !holds(&before[pl_id], cond) && holds(&after[pl_id], cond),
+            })
+            .map(|&(id, _)| id)
+            .collect()
     }
 }
+
 pub enum AsyncExecutorMsg {
     Tick,
+    /// rejected inputs are counted in the statistics and not used, see `InputPolicy`
     Input(Vec<(usize, UnifiedToken)>),
+    TakeSnapshot(Sender<ExecutorSnapshot>),
+    LoadSnapshot(ExecutorSnapshot, Sender<Result<(), SnapshotError>>),
+    GetMarking(Sender<Vec<UnifiedToken>>),
+    GetStatistics(Sender<ExecutorStatistics>),
+    /// back to the initial marking, delayed firings are dropped
+    Reset(Sender<()>),
+    /// fault injection: merges the token into any place, the error is a wrong place or token
+    /// or a fault the fault policy aborted on
+    InjectToken(usize, UnifiedToken, Sender<Result<(), ExecutionError>>),
+    /// the table must fit the arcs of the transition, the marking is kept
+    ReplaceTable(usize, UnifiedTableE, Sender<Result<(), HotSwapError>>),
+    SetPlaceScale(usize, f32, Sender<Result<(), HotSwapError>>),
+    /// Ticks are dropped until `Resume`, the delays do not advance for them and they are not made up.
+    /// The clock of the threaded executor keeps sending them, `ExecutorStatistics::paused_ticks`
+    /// counts them. Inputs and the other messages are still processed.
+    Pause(Sender<()>),
+    Resume(Sender<()>),
     Stop,
 }
 
+/// Runs the net on the messages of its channel, ticks included, until `Stop`.
+/// While paused the ticks are dropped, see `AsyncExecutorMsg::Pause`.
 pub struct AsynchronousUnifiedPetriExecutor{
     basic : BasicUnifiedPetriExecutor,
     tx : Sender<AsyncExecutorMsg>,
@@ -296,55 +1429,134 @@ impl AsynchronousUnifiedPetriExecutor {
         self.tx.clone()
     }
 
+    pub fn settings(&self) -> &ExecutorSettings {
+        &self.basic.settings
+    }
+
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        self.basic.set_settings(settings);
+    }
+
+    pub fn set_loop_limit_handler(&mut self, handler: Box<LoopLimitHandler>) {
+        self.basic.loop_limit_handler = Some(handler);
+    }
+
+    pub fn set_trace_recorder(&mut self, recorder: TraceRecorder) {
+        self.basic.set_trace(recorder);
+    }
+
+    /// The steps recorded in memory since the last call, input messages are steps of their own
+    pub fn take_trace(&mut self) -> Vec<TickTrace> {
+        self.basic.trace.as_mut().map_or(vec![], |trace| trace.take())
+    }
+
+    pub fn take_snapshot(&self) -> ExecutorSnapshot {
+        self.basic.take_snapshot()
+    }
+
+    pub fn load_snapshot(&mut self, snap: ExecutorSnapshot) -> Result<(), SnapshotError> {
+        self.basic.load_snapshot(snap)
+    }
+
+    pub fn get_net(&self) -> &UnifiedPetriNet {
+        &self.basic.net
+    }
+
+    pub fn get_marking(&self) -> &Vec<UnifiedToken> {
+        self.basic.get_marking()
+    }
+
+    pub fn get_place_token(&self, pl_id: usize) -> &UnifiedToken {
+        &self.basic.place_state[pl_id]
+    }
+
+    /// The places holding a token, with their token
+    pub fn marked_places<'a>(&'a self) -> impl Iterator<Item=(usize, &'a UnifiedToken)> + 'a {
+        self.basic.marked_places()
+    }
+
+    /// Ticks left until the transition puts its held tokens out, 0 if it is not firing
+    pub fn get_remaining_delay(&self, tr_id: usize) -> i32 {
+        self.basic.trans_state[tr_id]
+    }
+
+    /// The output tokens of a delayed transition, empty if it is not firing
+    pub fn get_held_tokens(&self, tr_id: usize) -> &Vec<UnifiedToken> {
+        &self.basic.trans_holds[tr_id]
+    }
+
+    pub fn get_statistics(&self) -> &ExecutorStatistics {
+        &self.basic.stats
+    }
+
     pub fn run(&mut self){
         loop {
             let rez = self.rx.recv().unwrap();
-            match rez {
-               AsyncExecutorMsg::Tick
-                   => {self.basic.update_delay_state();
-                       self.basic.execute_firable_transitions(); },
-               AsyncExecutorMsg::Input(v)
-                   => {self.basic.put_tokens_to_inp_places(v);
-                       self.basic.execute_firable_transitions();},
-               AsyncExecutorMsg::Stop => break,
+            if !self.basic.handle_msg(rez) {
+                break;
             }
         }
     }
 }
 
+/// The asynchronous executor on its own thread, ticked by a clock.
+/// A pause does not stop the clock, its ticks are dropped, see `AsyncExecutorMsg::Pause`.
 pub struct AsynchronousThreadedUnifiedPetriExecutor{
     basic : BasicUnifiedPetriExecutor,
     tx : Sender<AsyncExecutorMsg>,
     rx : Receiver<AsyncExecutorMsg>,
-    dur : Duration,
+    clock : Box<Clock>,
 }
 
+/// Keeps the clock ticking and owns the executor thread.
+/// Dropping the guard stops the executor without waiting for it, `stop` waits.
 pub struct ExecutorGuard {
-    timerGuard: Guard,
+    clock_guard: Option<Box<Send>>,
     sender : Sender<AsyncExecutorMsg>,
-    t: Timer,
+    handle: Option<JoinHandle<ExecutorSnapshot>>,
 }
 
 impl ExecutorGuard {
-    pub fn stop(self) {/* the drop should resolve it*/}
+    /// Stops the ticks and the thread, then returns the final state,
+    /// or the panic payload if a table or a consumer panicked on the executor thread
+    pub fn stop(mut self) -> thread::Result<ExecutorSnapshot> {
+        self.clock_guard = None;
+        // the thread is gone already if it panicked
+        let _ = self.sender.send(AsyncExecutorMsg::Stop);
+        self.handle.take().expect("the handle is only taken by stop").join()
+    }
+
+    /// False once the executor thread has finished, either stopped or panicked,
+    /// `stop` tells which one
+    pub fn is_alive(&self) -> bool {
+        self.handle.as_ref().map_or(false, |handle| !handle.is_finished())
+    }
 }
 
 impl Drop for ExecutorGuard{
     fn drop(&mut self) {
-        self.sender.send(AsyncExecutorMsg::Stop);
+        if self.handle.is_some() {
+            let _ = self.sender.send(AsyncExecutorMsg::Stop);
+        }
     }
 }
 
 impl AsynchronousThreadedUnifiedPetriExecutor {
     pub fn new(net: UnifiedPetriNet , men: EventManager, dur :Duration)
         -> AsynchronousThreadedUnifiedPetriExecutor {
+        AsynchronousThreadedUnifiedPetriExecutor::with_clock(net, men, Box::new(TimerClock::new(dur)))
+    }
+
+    /// Ticks come from the given clock instead of a timer, a `ManualClock` makes them deterministic
+    pub fn with_clock(net: UnifiedPetriNet , men: EventManager, clock: Box<Clock>)
+        -> AsynchronousThreadedUnifiedPetriExecutor {
 
         let (tx, rx) = channel();
         AsynchronousThreadedUnifiedPetriExecutor {
             basic: BasicUnifiedPetriExecutor::new(net, men),
             tx : tx,
             rx : rx,
-            dur : dur,
+            clock : clock,
         }
     }
 
@@ -352,37 +1564,53 @@ impl AsynchronousThreadedUnifiedPetriExecutor {
         self.tx.clone()
     }
 
-    pub fn start(self) -> ExecutorGuard {
-        let timer = Timer::new();
-        let sender = self.get_sender();
-        let guard = timer.schedule_repeating(self.dur, move ||{
-             sender.send(AsyncExecutorMsg::Tick).unwrap();
-        });
+    pub fn settings(&self) -> &ExecutorSettings {
+        &self.basic.settings
+    }
 
-        let exec_guard = ExecutorGuard {
-            timerGuard : guard,
-            sender : self.get_sender(),
-            t : timer,
-        };
+    pub fn set_settings(&mut self, settings: ExecutorSettings) {
+        self.basic.set_settings(settings);
+    }
 
-        thread::spawn (move || {
+    pub fn set_loop_limit_handler(&mut self, handler: Box<LoopLimitHandler>) {
+        self.basic.loop_limit_handler = Some(handler);
+    }
+
+    /// The executor runs on its own thread, only a streaming recorder gets the trace out
+    pub fn set_trace_sink(&mut self, sink: Box<Write + Send>) {
+        self.basic.set_trace(TraceRecorder::streaming(sink));
+    }
+
+    pub fn take_snapshot(&self) -> ExecutorSnapshot {
+        self.basic.take_snapshot()
+    }
+
+    /// Restores the state before `start`, a running executor takes the `LoadSnapshot` message
+    pub fn load_snapshot(&mut self, snap: ExecutorSnapshot) -> Result<(), SnapshotError> {
+        self.basic.load_snapshot(snap)
+    }
+
+    pub fn start(mut self) -> ExecutorGuard {
+        let sender = self.get_sender();
+        let clock_guard = self.clock.start(sender);
+        let exec_sender = self.get_sender();
+
+        let handle = thread::spawn (move || {
             let mut s = self;
             loop {
                 let rez = s.rx.recv().unwrap();
-                match rez {
-                   AsyncExecutorMsg::Tick=> {
-                       s.basic.update_delay_state();
-                       s.basic.execute_firable_transitions();
-                   },
-                   AsyncExecutorMsg::Input(v)
-                       => {s.basic.put_tokens_to_inp_places(v);
-                           s.basic.execute_firable_transitions();},
-                   AsyncExecutorMsg::Stop => break,
+                if !s.basic.handle_msg(rez) {
+                    break;
                 }
             }
-
+            s.basic.take_snapshot()
         });
-        exec_guard
+
+        ExecutorGuard {
+            clock_guard : Some(clock_guard),
+            sender : exec_sender,
+            handle : Some(handle),
+        }
     }
 }
 
@@ -392,6 +1620,8 @@ mod tests {
     use super::*;
     use tables::*;
     use unified_petri_net::net_builder::*;
+    use unified_petri_net::clock::ManualClock;
+    use unified_petri_net::trace::ConflictChoice;
     use std::sync::*;
 
     struct History{
@@ -462,7 +1692,7 @@ mod tests {
 
 
         let mut consumer_factory = ConsumerFactory::new();
-        let (net, mut event_manager) = bld.build();
+        let (net, mut event_manager) = bld.build().unwrap();
         event_manager.add(ot2, consumer_factory.create_handler_for(ot2));
 
         (net, event_manager, consumer_factory)
@@ -475,17 +1705,995 @@ mod tests {
        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
 
        let inp = vec![(0, UnifiedToken::from_val(0.0))];
-       exec.run_tick(inp);
+       exec.run_tick(inp).unwrap();
 
        let rez = cons_fact.get_current_hist();
        assert!(rez.len() == 0);
 
        let inp = vec![];
-       exec.run_tick(inp);
+       exec.run_tick(inp).unwrap();
 
        let rez = cons_fact.get_current_hist();
        assert_eq!(vec![(1,UnifiedToken::Exist(0.0))],rez);
     }
 
+    #[test]
+    fn value_dependent_delay_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(10.0);
+        let t0 = bld.add_transition(1,
+                                    UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_delay_multiplier(t0, 0.5);
+        bld.connect_place_with_transition(ip0, t0);
+        let p1 = bld.add_place(10.0);
+        bld.connect_transition_with_place(t0, p1);
+        let ot1 = bld.add_out_transition(
+            UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, ot1);
+
+        let mut consumer_factory = ConsumerFactory::new();
+        let (net, mut event_manager) = bld.build().unwrap();
+        event_manager.add(ot1, consumer_factory.create_handler_for(ot1));
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+
+        // 1 + 0.5 * 4.0 = 3 ticks
+        exec.run_tick(vec![(ip0, UnifiedToken::from_val(4.0))]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        assert!(consumer_factory.get_current_hist().is_empty());
+        exec.run_tick(vec![]).unwrap();
+        assert_eq!(vec![(ot1, UnifiedToken::Exist(4.0))], consumer_factory.get_current_hist());
+    }
+
+    #[test]
+    fn weighted_arc_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(1.0);
+        let ot0 = bld.add_out_transition(
+            UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition_with_weight(ip0, ot0, 0.5);
+
+        let mut consumer_factory = ConsumerFactory::new();
+        let (net, mut event_manager) = bld.build().unwrap();
+        event_manager.add(ot0, consumer_factory.create_handler_for(ot0));
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+
+        exec.run_tick(vec![(ip0, UnifiedToken::from_val(1.0))]).unwrap();
+        assert_eq!(vec![(ot0, UnifiedToken::Exist(0.5))], consumer_factory.get_current_hist());
+    }
+
+    struct DiagCollector {
+        diags: Arc<RwLock<Vec<LoopLimitDiagnostic>>>,
+    }
+
+    impl LoopLimitHandler for DiagCollector {
+        fn loop_limit_hit(&mut self, diag: &LoopLimitDiagnostic) {
+            self.diags.write().unwrap().push(diag.clone());
+        }
+    }
+
+    #[test]
+    fn livelock_is_reported_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let p0 = bld.add_place(1.0);
+        bld.set_innitial_marking(p0, UnifiedToken::from_val(0.0));
+        let p1 = bld.add_place(1.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p0, t0);
+        bld.connect_transition_with_place(t0, p1);
+        let t1 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, t1);
+        bld.connect_transition_with_place(t1, p0);
+        let (net, event_manager) = bld.build().unwrap();
+
+        let diags = Arc::new(RwLock::new(vec![]));
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_settings(ExecutorSettings{max_loop: 4, ..ExecutorSettings::default()});
+        exec.set_loop_limit_handler(Box::new(DiagCollector{diags: diags.clone()}));
+        exec.run_tick(vec![]).unwrap();
+
+        let diags = diags.read().unwrap();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].max_loop, 4);
+        assert_eq!(diags[0].fireable, vec![t0]);
+        assert_eq!(diags[0].cycle, vec![t0, t1]);
+        assert!(diags[0].is_livelock());
+    }
+
+    #[test]
+    fn long_chain_needs_more_loops_test(){
+        let chain = |max_loop| {
+            let mut bld = UnifiedPetriNetBuilder::new();
+            let mut prev = bld.add_place(1.0);
+            bld.set_innitial_marking(prev, UnifiedToken::from_val(0.0));
+            for _ in 0..4 {
+                let tr = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+                let next = bld.add_place(1.0);
+                bld.connect_place_with_transition(prev, tr);
+                bld.connect_transition_with_place(tr, next);
+                prev = next;
+            }
+            let (net, event_manager) = bld.build().unwrap();
+            let diags = Arc::new(RwLock::new(vec![]));
+            let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+            exec.set_settings(ExecutorSettings{max_loop: max_loop, ..ExecutorSettings::default()});
+            exec.set_loop_limit_handler(Box::new(DiagCollector{diags: diags.clone()}));
+            exec.run_tick(vec![]).unwrap();
+            let to_ret = diags.read().unwrap().clone();
+            to_ret
+        };
+
+        let diags = chain(2);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].fireable, vec![2]);
+        assert!(!diags[0].is_livelock());
+        assert!(chain(4).is_empty());
+    }
+
+    #[test]
+    fn trace_recorder_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_trace_recorder(TraceRecorder::in_memory());
+
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        let trace = exec.take_trace();
+        assert_eq!(trace.len(), 2);
+
+        assert_eq!(trace[0].tick, 0);
+        assert_eq!(trace[0].inputs, vec![(0, UnifiedToken::from_val(0.0))]);
+        assert_eq!(trace[0].started.len(), 1);
+        assert_eq!(trace[0].started[0].tr_id, 0);
+        assert_eq!(trace[0].started[0].inputs.len(), 2);
+        assert_eq!(trace[0].started[0].delay, 1);
+        assert!(trace[0].finished.is_empty());
+        assert_eq!(trace[0].holds.len(), 1);
+        assert_eq!(trace[0].holds[0].0, 0);
+        assert_eq!(trace[0].marking, vec![UnifiedToken::Phi; 4]);
+
+        assert_eq!(trace[1].tick, 1);
+        let finished : Vec<usize> = trace[1].finished.iter().map(|f| f.tr_id).collect();
+        assert_eq!(finished, vec![0, 1, 2]);
+        assert!(trace[1].holds.is_empty());
+        assert_eq!(trace[1].marking[1], UnifiedToken::from_val(0.0));
+
+        let line = trace[0].to_json_line();
+        assert!(line.starts_with("{\"conflicts\":[],\"finished\":[],\"holds\":[[0,"), "{}", line);
+        assert!(line.contains("\"policy\":\"staticPriority\""), "{}", line);
+        assert!(line.contains("\"inputs\":[[0,0.0]]"), "{}", line);
+        assert!(exec.take_trace().is_empty());
+    }
+
+    #[derive(Clone)]
+    struct SharedBuf {
+        buf: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Write for SharedBuf {
+        fn write(&mut self, data: &[u8]) -> ::std::io::Result<usize> {
+            self.buf.lock().unwrap().write(data)
+        }
+        fn flush(&mut self) -> ::std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn async_trace_is_streamed_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = AsynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let shared = SharedBuf{buf: Arc::new(Mutex::new(vec![]))};
+        exec.set_trace_recorder(TraceRecorder::streaming(Box::new(shared.clone())));
+        let sender = exec.getSender();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+
+        let text = String::from_utf8(shared.buf.lock().unwrap().clone()).unwrap();
+        let lines : Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("\"tick\":0}"), "{}", lines[0]);
+        assert!(lines[1].ends_with("\"tick\":0}"), "{}", lines[1]);
+        assert!(lines[2].ends_with("\"tick\":1}"), "{}", lines[2]);
+        assert!(exec.take_trace().is_empty());
+    }
+
+    struct BrokenSink;
+
+    impl Write for BrokenSink {
+        fn write(&mut self, _: &[u8]) -> ::std::io::Result<usize> {
+            Err(::std::io::Error::new(::std::io::ErrorKind::Other, "disk full"))
+        }
+        fn flush(&mut self) -> ::std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn broken_trace_sink_falls_back_to_memory_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_trace_recorder(TraceRecorder::streaming(Box::new(BrokenSink)));
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        let ticks : Vec<u64> = exec.take_trace().iter().map(|tr| tr.tick).collect();
+        assert_eq!(ticks, vec![0, 1]);
+    }
+
+    struct PanickingConsumer;
+
+    impl UnifiedTokenConsumer for PanickingConsumer {
+        fn consume(&mut self, _: UnifiedToken){
+            panic!("consumer failed");
+        }
+    }
+
+    #[test]
+    fn threaded_stop_returns_final_state_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let exec = AsynchronousThreadedUnifiedPetriExecutor::new(net, event_manager, Duration::hours(1));
+        let sender = exec.get_sender();
+        let guard = exec.start();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        assert!(guard.is_alive());
+
+        let snap = guard.stop().unwrap();
+        assert_eq!(snap.trans_state[0], 1);
+        assert_eq!(snap.trans_holds[0].len(), 2);
+    }
+
+    #[test]
+    fn threaded_panic_is_reported_test(){
+        let (net, mut event_manager, _) = simple_delay_net();
+        event_manager.add(1, Box::new(PanickingConsumer));
+        let exec = AsynchronousThreadedUnifiedPetriExecutor::new(net, event_manager, Duration::hours(1));
+        let sender = exec.get_sender();
+        let guard = exec.start();
+        let (inj_tx, _inj_rx) = channel();
+        sender.send(AsyncExecutorMsg::InjectToken(2, UnifiedToken::from_val(0.0), inj_tx)).unwrap();
+
+        let mut waited = 0;
+        while guard.is_alive() && waited < 500 {
+            thread::sleep(::std::time::Duration::from_millis(10));
+            waited += 1;
+        }
+        assert!(!guard.is_alive());
+        let payload = guard.stop().unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"consumer failed"));
+    }
+
+    #[test]
+    fn manual_clock_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let clock = ManualClock::new();
+        assert!(!clock.advance(1));
+        let exec = AsynchronousThreadedUnifiedPetriExecutor::with_clock(net, event_manager,
+                                                                        Box::new(clock.clone()));
+        let sender = exec.get_sender();
+        let guard = exec.start();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        assert!(clock.advance(0));
+        assert!(cons_fact.get_current_hist().is_empty());
+
+        assert!(clock.advance(1));
+        assert_eq!(vec![(1,UnifiedToken::Exist(0.0))], cons_fact.get_current_hist());
+        assert!(clock.advance(3));
+        let (stat_tx, stat_rx) = channel();
+        sender.send(AsyncExecutorMsg::GetStatistics(stat_tx)).unwrap();
+        assert_eq!(stat_rx.recv().unwrap().ticks, 4);
+
+        guard.stop().unwrap();
+        assert!(!clock.advance(1));
+    }
+
+    #[test]
+    fn threaded_pause_drops_ticks_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let clock = ManualClock::new();
+        let exec = AsynchronousThreadedUnifiedPetriExecutor::with_clock(net, event_manager,
+                                                                        Box::new(clock.clone()));
+        let sender = exec.get_sender();
+        let guard = exec.start();
+        let (ctrl_tx, ctrl_rx) = channel();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::Pause(ctrl_tx.clone())).unwrap();
+        ctrl_rx.recv().unwrap();
+
+        // t0 is still holding its tokens, the clock keeps ticking
+        assert!(clock.advance(2));
+        assert!(cons_fact.get_current_hist().is_empty());
+
+        sender.send(AsyncExecutorMsg::Resume(ctrl_tx)).unwrap();
+        ctrl_rx.recv().unwrap();
+        assert!(clock.advance(1));
+        assert_eq!(vec![(1,UnifiedToken::Exist(0.0))], cons_fact.get_current_hist());
+
+        let (stat_tx, stat_rx) = channel();
+        sender.send(AsyncExecutorMsg::GetStatistics(stat_tx)).unwrap();
+        let stats = stat_rx.recv().unwrap();
+        assert_eq!(stats.ticks, 1);
+        assert_eq!(stats.paused_ticks, 2);
+        guard.stop().unwrap();
+    }
+
+    /// ip0 feeds t0 and t1, each passes the token to its own out transition, ot2 and ot3
+    fn conflict_net(t0_table: UnifiedOneXOneTable, t1_priority: i32)
+        -> (UnifiedPetriNet, EventManager, ConsumerFactory) {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(1.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::oxo(t0_table));
+        let t1 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_priority(t1, t1_priority);
+        bld.connect_place_with_transition(ip0, t0);
+        bld.connect_place_with_transition(ip0, t1);
+        let p1 = bld.add_place(1.0);
+        let p2 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p1);
+        bld.connect_transition_with_place(t1, p2);
+        let ot2 = bld.add_out_transition(UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        let ot3 = bld.add_out_transition(UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p1, ot2);
+        bld.connect_place_with_transition(p2, ot3);
+
+        let mut consumer_factory = ConsumerFactory::new();
+        let (net, mut event_manager) = bld.build().unwrap();
+        event_manager.add(ot2, consumer_factory.create_handler_for(ot2));
+        event_manager.add(ot3, consumer_factory.create_handler_for(ot3));
+        (net, event_manager, consumer_factory)
+    }
+
+    fn conflict_winners(t0_table: UnifiedOneXOneTable, t1_priority: i32, policy: ConflictPolicy,
+                        value: f32, ticks: usize) -> Vec<usize> {
+        let (net, event_manager, cons_fact) = conflict_net(t0_table, t1_priority);
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_settings(ExecutorSettings{conflict_policy: policy, ..ExecutorSettings::default()});
+        for _ in 0..ticks {
+            exec.run_tick(vec![(0, UnifiedToken::from_val(value))]).unwrap();
+        }
+        cons_fact.get_current_hist().into_iter().map(|(tr_id, _)| tr_id).collect()
+    }
+
+    #[test]
+    fn conflict_policy_test(){
+        let default = UnifiedOneXOneTable::default_table;
+        assert_eq!(conflict_winners(default(), 0, ConflictPolicy::StaticPriority, 0.0, 3), vec![2, 2, 2]);
+        assert_eq!(conflict_winners(default(), 1, ConflictPolicy::StaticPriority, 0.0, 3), vec![3, 3, 3]);
+        assert_eq!(conflict_winners(default(), 0, ConflictPolicy::RoundRobin, 0.0, 4), vec![2, 3, 2, 3]);
+        // priority wins over the policy
+        assert_eq!(conflict_winners(default(), 1, ConflictPolicy::RoundRobin, 0.0, 2), vec![3, 3]);
+
+        let random = conflict_winners(default(), 0, ConflictPolicy::SeededRandom(7), 0.0, 20);
+        assert_eq!(random, conflict_winners(default(), 0, ConflictPolicy::SeededRandom(7), 0.0, 20));
+        assert!(random.contains(&2) && random.contains(&3));
+
+        // 0.1 is 0.8 ZR and 0.2 PM, t0 only has a rule for PM
+        let pm_only = || UnifiedOneXOneTable::from_arr(
+            [TableValue::Phi, TableValue::Phi, TableValue::Phi, TableValue::E(FuzzyValue::PM),
+             TableValue::Phi, TableValue::Phi]);
+        assert_eq!(conflict_winners(pm_only(), 0, ConflictPolicy::StaticPriority, 0.1, 1), vec![2]);
+        assert_eq!(conflict_winners(pm_only(), 0, ConflictPolicy::HighestActivation, 0.1, 1), vec![3]);
+    }
+
+    #[test]
+    fn unrelated_transitions_do_not_conflict_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(1.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        let t1 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(ip0, t0);
+        bld.connect_place_with_transition(ip0, t1);
+        let ip1 = bld.add_inp_place(1.0);
+        let t2 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(ip1, t2);
+        for &tr in [t0, t1, t2].iter() {
+            let pl = bld.add_place(1.0);
+            bld.connect_transition_with_place(tr, pl);
+        }
+        let (net, event_manager) = bld.build().unwrap();
+
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_trace_recorder(TraceRecorder::in_memory());
+        exec.set_settings(ExecutorSettings{conflict_policy: ConflictPolicy::RoundRobin, ..ExecutorSettings::default()});
+        for _ in 0..4 {
+            exec.run_tick(vec![(ip0, UnifiedToken::from_val(0.0)), (ip1, UnifiedToken::from_val(0.0))]).unwrap();
+        }
+        let trace = exec.take_trace();
+        let chosen : Vec<usize> = trace.iter()
+            .flat_map(|tick| tick.conflicts.iter().map(|conflict| conflict.chosen))
+            .collect();
+        assert_eq!(chosen, vec![t0, t1, t0, t1]);
+        assert!(trace.iter().all(|tick| tick.conflicts.iter().all(|conflict| conflict.candidates == vec![t0, t1])));
+        assert!(trace.iter().all(|tick| tick.started.iter().any(|start| start.tr_id == t2)));
+    }
+
+    #[test]
+    fn conflicts_are_traced_test(){
+        let (net, event_manager, _) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_trace_recorder(TraceRecorder::in_memory());
+        exec.set_settings(ExecutorSettings{conflict_policy: ConflictPolicy::RoundRobin, ..ExecutorSettings::default()});
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        let trace = exec.take_trace();
+        assert_eq!(trace[0].policy, "roundRobin");
+        assert_eq!(trace[0].conflicts, vec![ConflictChoice{candidates: vec![0, 1], chosen: 0}]);
+        assert_eq!(trace[1].conflicts, vec![ConflictChoice{candidates: vec![0, 1], chosen: 1}]);
+        assert!(trace[1].to_json_line().contains("\"conflicts\":[{\"candidates\":[0,1],\"chosen\":1}]"));
+    }
+
+    fn run_batch(threads: usize) -> (BatchUnifiedPetriExecutor, Vec<Vec<TickResult>>) {
+        let (net, _, _) = simple_delay_net();
+        let mut batch = BatchUnifiedPetriExecutor::new(net, 5);
+        batch.set_threads(threads);
+        let first = (0..5)
+            .map(|i| if i % 2 == 0 { vec![(0, UnifiedToken::from_val(0.0))] } else { vec![] })
+            .collect();
+        let mut ticks = vec![];
+        for inps in vec![first, vec![vec![]; 5]] {
+            ticks.push(batch.run_tick(inps).into_iter().map(|rez| rez.unwrap()).collect());
+        }
+        (batch, ticks)
+    }
+
+    #[test]
+    fn batch_executor_test(){
+        let (batch, ticks) = run_batch(1);
+        assert!(Arc::ptr_eq(&batch.instances[0].net, &batch.instances[4].net));
+        assert!(Arc::ptr_eq(&batch.instances[0].cached_possibly_exec, &batch.instances[4].cached_possibly_exec));
+        assert!(ticks[0].iter().all(|rez| rez.outputs.is_empty()));
+        for i in 0..5 {
+            let expected = if i % 2 == 0 { vec![(1, UnifiedToken::from_val(0.0))] } else { vec![] };
+            assert_eq!(ticks[1][i].outputs, expected);
+        }
+        assert_eq!(batch.get_statistics(0).firings, 3);
+        assert_eq!(batch.get_statistics(1).firings, 0);
+        assert_eq!(batch.get_marking(1), batch.get_marking(3));
+
+        let (parallel, parallel_ticks) = run_batch(3);
+        assert_eq!(parallel_ticks, ticks);
+        for i in 0..5 {
+            assert_eq!(parallel.take_snapshot(i), batch.take_snapshot(i));
+        }
+    }
+
+    #[test]
+    fn hot_swap_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let never = || UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr([TableValue::Phi; 6]));
+        exec.replace_table(2, never()).unwrap();
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        assert!(exec.get_place_token(3).not_phi());
+        assert!(!exec.get_place_token(1).not_phi());
+
+        // the cached marking said t1 can not fire, the new table can
+        exec.replace_table(2, UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr(
+            [TableValue::E(FuzzyValue::PL); 6]))).unwrap();
+        exec.set_place_scale(1, 2.0).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        assert_eq!(exec.get_place_token(1), &UnifiedToken::from_val(2.0));
+        assert_eq!(exec.get_net().get_place_scale(1), 2.0);
+
+        assert_eq!(exec.replace_table(9, never()), Err(HotSwapError::NoSuchTransition(9)));
+        assert_eq!(exec.replace_table(0, never()), Err(HotSwapError::TableDoesNotFit(vec![
+            StructuralProblem::WrongInputNr{tr_id: 0, expected: 1, found: 2},
+            StructuralProblem::WrongOutputNr{tr_id: 0, expected: 1, found: 2}])));
+        assert_eq!(exec.set_place_scale(9, 1.0), Err(HotSwapError::NoSuchPlace(9)));
+        assert_eq!(exec.set_place_scale(1, 0.0), Err(HotSwapError::WrongScale(0.0)));
+    }
+
+    #[test]
+    fn async_hot_swap_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = AsynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let sender = exec.getSender();
+        let (tx, rx) = channel();
+        sender.send(AsyncExecutorMsg::ReplaceTable(2, UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr(
+            [TableValue::E(FuzzyValue::PL); 6])), tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::ReplaceTable(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()),
+                                                   tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::SetPlaceScale(1, 3.0, tx)).unwrap();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+
+        assert!(rx.recv().unwrap().is_ok());
+        assert!(rx.recv().unwrap().is_err());
+        assert!(rx.recv().unwrap().is_ok());
+        assert_eq!(exec.get_place_token(1), &UnifiedToken::from_val(3.0));
+    }
+
+    #[test]
+    fn debugger_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut dbg = UnifiedPetriDebugger::new(net, event_manager);
+        let t1_fires = dbg.add_breakpoint(Breakpoint::Transition(2));
+        let p1_marked = dbg.add_breakpoint(Breakpoint::PlaceMarked(2));
+        let p0_positive = dbg.add_breakpoint(Breakpoint::TokenValue(1, Box::new(|v| v >= 0.0)));
+        dbg.add_inputs(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+
+        let step = dbg.step();
+        assert_eq!(step.event, StepEvent::TickStarted{
+            tick: 0, inputs: vec![(0, UnifiedToken::from_val(0.0))], finished: vec![]});
+        assert!(step.hits.is_empty());
+        match dbg.step().event {
+            StepEvent::Fired(report) => {
+                assert_eq!(report.start.tr_id, 0);
+                assert_eq!(report.start.delay, 1);
+                assert_eq!(report.activation, 1.0);
+                assert!(report.competing.is_empty());
+                assert!(report.finished.is_empty());
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(dbg.step().event, StepEvent::TickEnded{tick: 0, outputs: vec![], loop_limit: None});
+        assert!(!dbg.is_in_tick());
+
+        let step = dbg.run_to_breakpoint(10).unwrap();
+        assert_eq!(step.hits, vec![p1_marked]);
+        match step.event {
+            StepEvent::TickStarted{tick, ref finished, ..} => {
+                assert_eq!(tick, 1);
+                assert_eq!(finished.iter().map(|f| f.tr_id).collect::<Vec<_>>(), vec![0]);
+            },
+            ref other => panic!("unexpected {:?}", other),
+        }
+        assert!(cons_fact.get_current_hist().is_empty());
+
+        let step = dbg.run_to_breakpoint(10).unwrap();
+        assert_eq!(step.hits, vec![t1_fires, p0_positive]);
+        assert_eq!(cons_fact.get_current_hist(), vec![(1, UnifiedToken::from_val(0.0))]);
+
+        assert!(dbg.remove_breakpoint(t1_fires));
+        assert!(!dbg.remove_breakpoint(t1_fires));
+        let rest = dbg.finish_tick();
+        assert_eq!(rest.last().unwrap().event, StepEvent::TickEnded{
+            tick: 1, outputs: vec![(1, UnifiedToken::from_val(0.0))], loop_limit: None});
+        assert_eq!(dbg.take_trace().len(), 2);
+    }
+
+    #[test]
+    fn debugger_reports_competitors_test(){
+        let (net, event_manager, _) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut dbg = UnifiedPetriDebugger::new(net, event_manager);
+        dbg.add_inputs(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        dbg.step();
+        match dbg.step().event {
+            StepEvent::Fired(report) => {
+                assert_eq!(report.start.tr_id, 0);
+                assert_eq!(report.competing, vec![1]);
+                assert_eq!(report.finished.len(), 1);
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn snapshot_survives_restart_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        let snap = exec.take_snapshot();
+        assert_eq!(snap.trans_state[0], 1);
+        assert_eq!(snap.trans_holds[0].len(), 2);
+
+        let fname = ::std::env::temp_dir().join("ruzzp_snapshot_test.json");
+        let fname = fname.to_str().unwrap();
+        snap.write_to_file(fname).unwrap();
+        let read_back = ExecutorSnapshot::read_from_file(fname).unwrap();
+        assert_eq!(snap, read_back);
+
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut restarted = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        restarted.load_snapshot(read_back).unwrap();
+        restarted.run_tick(vec![]).unwrap();
+        assert_eq!(vec![(1,UnifiedToken::Exist(0.0))], cons_fact.get_current_hist());
+    }
+
+    #[test]
+    fn snapshot_keeps_conflict_turns_test(){
+        let round_robin = ExecutorSettings{conflict_policy: ConflictPolicy::RoundRobin, ..ExecutorSettings::default()};
+        let (net, event_manager, _) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_settings(round_robin);
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        let snap = ExecutorSnapshot::from_json(&exec.take_snapshot().to_json()).unwrap();
+        assert_eq!(snap.last_winner, Some(0));
+
+        let (net, event_manager, cons_fact) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut restarted = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        restarted.set_settings(round_robin);
+        restarted.load_snapshot(snap).unwrap();
+        restarted.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        // t1 won the conflict, its token reaches ot3
+        assert_eq!(vec![(3, UnifiedToken::Exist(0.0))], cons_fact.get_current_hist());
+
+        let seeded = ExecutorSettings{conflict_policy: ConflictPolicy::SeededRandom(7), ..ExecutorSettings::default()};
+        let winners = |exec: &mut SynchronousUnifiedPetriExecutor, ticks: usize| -> Vec<usize> {
+            (0..ticks).map(|_| exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap().outputs[0].0).collect()
+        };
+        let (net, event_manager, _) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_settings(seeded);
+        winners(&mut exec, 3);
+        let snap = exec.take_snapshot();
+        let expected = winners(&mut exec, 10);
+        let (net, event_manager, _) = conflict_net(UnifiedOneXOneTable::default_table(), 0);
+        let mut restarted = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        restarted.set_settings(seeded);
+        restarted.load_snapshot(snap).unwrap();
+        assert_eq!(winners(&mut restarted, 10), expected);
+
+        let old = ExecutorSnapshot::from_json(
+            "{\"placeState\": [null], \"transState\": [0], \"transHolds\": [[]]}").unwrap();
+        assert_eq!((old.last_winner, old.rng_state), (None, 0));
+    }
+
+    #[test]
+    fn snapshot_shape_is_checked_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let good = exec.take_snapshot();
+
+        let mut short = good.clone();
+        short.place_state.pop();
+        assert!(exec.load_snapshot(short).is_err());
+
+        let mut holding = good.clone();
+        holding.trans_state[0] = 2;
+        holding.trans_holds[0] = vec![UnifiedToken::Phi];
+        match exec.load_snapshot(holding) {
+            Err(SnapshotError::ShapeMismatch(msg)) => assert_eq!(msg, "T0 holds 1 tokens, its table produces 2"),
+            _ => panic!("mismatch expected"),
+        }
+        assert_eq!(exec.take_snapshot(), good);
+        assert!(ExecutorSnapshot::from_json("{\"placeState\": [true]}").is_err());
+    }
+
+    #[test]
+    fn input_policy_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let wrong_place = vec![(0, UnifiedToken::from_val(0.0)), (2, UnifiedToken::from_val(0.5))];
+        assert_eq!(exec.run_tick(wrong_place.clone()), Err(ExecutionError::InvalidInput(InputError::NotAnInputPlace(2))));
+        assert_eq!(exec.run_tick(vec![(9, UnifiedToken::from_val(0.5))]), Err(ExecutionError::InvalidInput(InputError::NoSuchPlace(9))));
+        assert_eq!(exec.get_statistics().ticks, 0);
+        assert_eq!(exec.get_place_token(0), &UnifiedToken::Phi);
+
+        let mut settings = *exec.settings();
+        settings.input_policy = InputPolicy::Ignore;
+        exec.set_settings(settings);
+        exec.run_tick(wrong_place).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        assert_eq!(vec![(1, UnifiedToken::Exist(0.0))], cons_fact.get_current_hist());
+        assert_eq!(exec.get_statistics().rejected_inputs, 3);
+        assert_eq!(exec.get_statistics().input_tokens, 1);
+
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut exec = AsynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let sender = exec.getSender();
+        let (stats_tx, stats_rx) = channel();
+        sender.send(AsyncExecutorMsg::Input(vec![(1, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::GetStatistics(stats_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+        assert_eq!(stats_rx.recv().unwrap().rejected_inputs, 1);
+        assert!(cons_fact.get_current_hist().is_empty());
+    }
+
+    fn nan_net(policy: FaultPolicy) -> (SynchronousUnifiedPetriExecutor, Result<TickResult, ExecutionError>) {
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(1.0);
+        let ip1 = bld.add_inp_place(1.0);
+        let t0 = bld.add_transition(1, UnifiedTableE::txo(UnifiedTwoXOneTable::all_pl(Operator::Minus)));
+        bld.connect_place_with_transition(ip0, t0);
+        bld.connect_place_with_transition(ip1, t0);
+        let p2 = bld.add_place(1.0);
+        bld.connect_transition_with_place(t0, p2);
+        let ot1 = bld.add_out_transition(UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.connect_place_with_transition(p2, ot1);
+        let (net, event_manager) = bld.build().unwrap();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.set_settings(ExecutorSettings{fault_policy: policy, ..ExecutorSettings::default()});
+        // inf - inf
+        let rez = exec.run_tick(vec![(ip0, UnifiedToken::Exist(::std::f32::INFINITY)),
+                                     (ip1, UnifiedToken::Exist(::std::f32::INFINITY))]);
+        (exec, rez)
+    }
+
+    #[test]
+    fn fault_policy_test(){
+        let (exec, rez) = nan_net(FaultPolicy::Abort);
+        assert_eq!(rez, Err(ExecutionError::NaNToken(NaNSource::Transition(0))));
+        assert_eq!(format!("{}", rez.unwrap_err()), "T0 produced a NaN token");
+        assert_eq!(exec.get_statistics().faults, 1);
+        assert!(exec.marked_places().next().is_none());
+
+        let (exec, rez) = nan_net(FaultPolicy::SkipTransition);
+        assert_eq!(rez, Ok(TickResult{outputs: vec![]}));
+        assert_eq!(exec.get_remaining_delay(0), 0);
+        assert_eq!(exec.get_statistics().faults, 1);
+
+        let (mut exec, rez) = nan_net(FaultPolicy::NaNToPhi);
+        assert!(rez.is_ok());
+        assert_eq!(exec.get_remaining_delay(0), 1);
+        assert_eq!(exec.get_held_tokens(0), &vec![UnifiedToken::Phi]);
+        exec.run_tick(vec![(0, UnifiedToken::from_val(::std::f32::NAN)), (1, UnifiedToken::from_val(2.0))]).unwrap();
+        assert_eq!(exec.get_place_token(0), &UnifiedToken::Phi);
+        assert_eq!(exec.get_place_token(1), &UnifiedToken::Exist(2.0));
+        assert_eq!(exec.get_statistics().faults, 2);
+        assert_eq!(exec.get_statistics().rejected_inputs, 0);
+
+        let (mut exec, _) = nan_net(FaultPolicy::Abort);
+        let rez = exec.run_tick(vec![(1, UnifiedToken::from_val(2.0)), (0, UnifiedToken::from_val(::std::f32::NAN))]);
+        assert_eq!(rez, Err(ExecutionError::NaNToken(NaNSource::InputPlace(0))));
+        assert_eq!(format!("{}", rez.unwrap_err()), "NaN input for P0");
+        assert_eq!(exec.get_place_token(1), &UnifiedToken::Phi);
+        assert_eq!(exec.inject_token(0, UnifiedToken::from_val(::std::f32::NAN)),
+                   Err(ExecutionError::NaNToken(NaNSource::InputPlace(0))));
+    }
+
+    #[test]
+    fn statistics_do_not_depend_on_tracing_test(){
+        let run = |traced: bool| {
+            // t1 has the higher priority and fires first, t0 would refuse the ZR token
+            let pm_only = UnifiedOneXOneTable::from_arr(
+                [TableValue::Phi, TableValue::Phi, TableValue::Phi, TableValue::E(FuzzyValue::PM),
+                 TableValue::Phi, TableValue::Phi]);
+            let (net, event_manager, _) = conflict_net(pm_only, 1);
+            let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+            if traced {
+                exec.set_trace_recorder(TraceRecorder::in_memory());
+            }
+            for _ in 0..3 {
+                exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+            }
+            exec.get_statistics().clone()
+        };
+        let untraced = run(false);
+        let traced = run(true);
+        assert_eq!(untraced, ExecutorStatistics{
+            last_tick_time: untraced.last_tick_time,
+            max_tick_time: untraced.max_tick_time,
+            total_tick_time: untraced.total_tick_time,
+            ..traced
+        });
+        assert_eq!(untraced.transitions[0].not_fireable, 0);
+        assert_eq!(untraced.transitions[1].firings, 3);
+    }
+
+    #[test]
+    fn transition_statistics_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        // ot2 fires only on NL tokens, it gets ZR ones
+        let mut nl_only = [TableValue::Phi; 6];
+        nl_only[0] = TableValue::E(FuzzyValue::ZR);
+        exec.replace_table(1, UnifiedTableE::oxo(UnifiedOneXOneTable::from_arr(nl_only))).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+        exec.run_tick(vec![]).unwrap();
+
+        let stats = exec.get_statistics();
+        let firings : Vec<u64> = stats.transitions.iter().map(|tr| tr.firings).collect();
+        assert_eq!(firings, vec![1, 0, 1]);
+        assert_eq!(stats.never_fired(), vec![1]);
+        assert_eq!(stats.transitions[0].delay_ticks, 1);
+        // checked twice in the tick p1 got its token, once in the next
+        assert_eq!(stats.transitions[1].not_fireable, 3);
+        assert_eq!(stats.max_loop_iterations, 1);
+        // one lookup per fire_next
+        assert_eq!(stats.cache_hits + stats.cache_misses, 6);
+        assert!(stats.cache_hit_ratio() > 0.0);
+        assert!(stats.max_tick_time >= stats.last_tick_time);
+        assert!(stats.total_tick_time >= stats.max_tick_time);
+        assert!(stats.mean_tick_time() <= stats.max_tick_time);
+    }
+
+    #[test]
+    fn merge_strategy_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(10.0);
+        bld.set_merge_strategy(ip0, MergeStrategy::Average);
+        let p1 = bld.add_place(10.0);
+        let t0 = bld.add_transition(0, UnifiedTableE::txo(UnifiedTwoXOneTable::default_table()));
+        bld.connect_place_with_transition(ip0, t0);
+        bld.connect_place_with_transition(p1, t0);
+        let p2 = bld.add_place(10.0);
+        bld.connect_transition_with_place(t0, p2);
+        let (net, event_manager) = bld.build().unwrap();
+
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net.clone(), event_manager);
+        exec.run_tick(vec![(ip0, UnifiedToken::from_val(3.0)), (ip0, UnifiedToken::from_val(6.0))]).unwrap();
+        let snap = exec.take_snapshot();
+        assert_eq!(snap.place_arrivals[ip0], 2);
+        let read_back = ExecutorSnapshot::from_json(&snap.to_json()).unwrap();
+        assert_eq!(snap, read_back);
+
+        let mut restarted = SynchronousUnifiedPetriExecutor::new(net, EventManager::new());
+        restarted.load_snapshot(read_back).unwrap();
+        restarted.run_tick(vec![(ip0, UnifiedToken::from_val(0.0))]).unwrap();
+        assert_eq!(restarted.get_place_token(ip0), &UnifiedToken::Exist(3.0));
+        restarted.reset();
+        assert_eq!(restarted.take_snapshot().place_arrivals, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn three_input_table_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let inps : Vec<usize> = (0..3).map(|_| bld.add_inp_place(10.0)).collect();
+        // PL wherever every input has a value, the sum scales it
+        let values = (0..216)
+            .map(|cell| if cell % 6 == 5 || cell / 6 % 6 == 5 || cell / 36 == 5 { TableValue::Phi } else { TableValue::E(FuzzyValue::PL) })
+            .collect();
+        let t0 = bld.add_transition(0, UnifiedTableE::nxm(UnifiedNXMTable::from_vec(3, 1, values, Operator::Plus)));
+        for &pl in inps.iter() {
+            bld.connect_place_with_transition(pl, t0);
+        }
+        let p3 = bld.add_place(10.0);
+        bld.connect_transition_with_place(t0, p3);
+        let (net, event_manager) = bld.build().unwrap();
+
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.run_tick(vec![(inps[0], UnifiedToken::from_val(1.0)), (inps[1], UnifiedToken::from_val(2.0))]).unwrap();
+        assert_eq!(exec.get_place_token(p3), &UnifiedToken::Phi);
+        exec.run_tick(vec![(inps[2], UnifiedToken::from_val(3.0))]).unwrap();
+        match *exec.get_place_token(p3) {
+            UnifiedToken::Exist(v) => assert!((v - 6.0).abs() < 1e-4, "{}", v),
+            UnifiedToken::Phi => panic!("t0 did not fire"),
+        }
+        assert_eq!(exec.get_place_token(inps[0]), &UnifiedToken::Phi);
+    }
+
+    #[test]
+    fn async_snapshot_messages_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = AsynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let sender = exec.getSender();
+        let (snap_tx, snap_rx) = channel();
+        let (load_tx, load_rx) = channel();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::TakeSnapshot(snap_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+        let snap = snap_rx.recv().unwrap();
+        assert_eq!(snap.trans_state[0], 1);
+
+        sender.send(AsyncExecutorMsg::LoadSnapshot(snap.clone(), load_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::TakeSnapshot(snap_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+        assert!(load_rx.recv().unwrap().is_ok());
+        assert_eq!(snap_rx.recv().unwrap(), snap);
+    }
+
+    #[test]
+    fn async_control_messages_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut exec = AsynchronousUnifiedPetriExecutor::new(net, event_manager);
+        let sender = exec.getSender();
+        let (mark_tx, mark_rx) = channel();
+        let (stat_tx, stat_rx) = channel();
+        let (ctrl_tx, ctrl_rx) = channel();
+        let (inj_tx, inj_rx) = channel();
+        sender.send(AsyncExecutorMsg::Pause(ctrl_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::Input(vec![(0, UnifiedToken::from_val(0.0))])).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::GetMarking(mark_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::Resume(ctrl_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::Tick).unwrap();
+        sender.send(AsyncExecutorMsg::GetMarking(mark_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::GetStatistics(stat_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::InjectToken(9, UnifiedToken::from_val(0.0), inj_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::InjectToken(2, UnifiedToken::from_val(0.5), inj_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::GetMarking(mark_tx.clone())).unwrap();
+        sender.send(AsyncExecutorMsg::Reset(ctrl_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::GetMarking(mark_tx)).unwrap();
+        sender.send(AsyncExecutorMsg::Stop).unwrap();
+        exec.run();
+
+        assert_eq!(ctrl_rx.iter().count(), 3);
+        let paused = mark_rx.recv().unwrap();
+        assert!(!paused[1].not_phi());
+        let resumed = mark_rx.recv().unwrap();
+        assert_eq!(resumed[1], UnifiedToken::from_val(0.0));
+
+        let stats = stat_rx.recv().unwrap();
+        assert_eq!(stats.ticks, 1);
+        assert_eq!(stats.paused_ticks, 1);
+        assert_eq!(stats.input_tokens, 1);
+        assert_eq!(stats.firings, 3);
+
+        assert!(inj_rx.recv().unwrap().is_err());
+        assert!(inj_rx.recv().unwrap().is_ok());
+        // the output transition consumes the injected token right away
+        let injected = mark_rx.recv().unwrap();
+        assert!(!injected[2].not_phi());
+        assert_eq!(cons_fact.get_current_hist().last(), Some(&(1, UnifiedToken::from_val(0.5))));
+        let after_reset = mark_rx.recv().unwrap();
+        assert_eq!(after_reset, exec.get_marking().clone());
+        assert_eq!(exec.marked_places().collect::<Vec<_>>(), vec![(1, &UnifiedToken::from_val(0.0))]);
+        assert_eq!(exec.get_statistics().ticks, 1);
+    }
+
+    #[test]
+    fn state_inspection_test(){
+        let (net, event_manager, _) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        assert_eq!(exec.marked_places().collect::<Vec<_>>(), vec![(1, &UnifiedToken::from_val(0.0))]);
+
+        exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        assert_eq!(exec.marked_places().count(), 0);
+        assert_eq!(exec.get_remaining_delay(0), 1);
+        assert_eq!(exec.get_held_tokens(0).len(), 2);
+        assert_eq!(exec.get_remaining_delay(2), 0);
+        assert!(exec.get_held_tokens(2).is_empty());
+
+        exec.run_tick(vec![]).unwrap();
+        assert_eq!(exec.get_remaining_delay(0), 0);
+        assert_eq!(exec.get_place_token(1), &UnifiedToken::from_val(0.0));
+        assert_eq!(exec.get_marking().len(), exec.get_net().get_place_nr());
+    }
+
+    #[test]
+    fn tick_result_test(){
+        let (net, event_manager, cons_fact) = simple_delay_net();
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+
+        let rez = exec.run_tick(vec![(0, UnifiedToken::from_val(0.0))]).unwrap();
+        assert!(rez.outputs.is_empty());
+        let rez = exec.run_tick(vec![]).unwrap();
+        assert_eq!(rez.outputs, vec![(1, UnifiedToken::Exist(0.0))]);
+        assert_eq!(rez.outputs, cons_fact.get_current_hist());
+        let rez = exec.run_tick(vec![]).unwrap();
+        assert!(rez.outputs.is_empty());
+    }
+
+    #[test]
+    fn named_inputs_and_handlers_test(){
+        let mut bld = UnifiedPetriNetBuilder::new();
+        let ip0 = bld.add_inp_place(1.0);
+        bld.set_place_name(ip0, "temp_in");
+        let ot0 = bld.add_out_transition(
+            UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_trans_name(ot0, "heater");
+        bld.connect_place_with_transition(ip0, ot0);
+
+        let mut consumer_factory = ConsumerFactory::new();
+        let handler = consumer_factory.create_handler_for(ot0);
+        assert_eq!(bld.add_action_for_named_out_trans("heater", handler), Ok(ot0));
+        let handler = consumer_factory.create_handler_for(ot0);
+        assert_eq!(bld.add_action_for_named_out_trans("cooler", handler),
+                   Err(HandlerError::NoSuchTransition("cooler".to_string())));
+        let t1 = bld.add_transition(0, UnifiedTableE::oxo(UnifiedOneXOneTable::default_table()));
+        bld.set_trans_name(t1, "mixer");
+        let ip1 = bld.add_inp_place(1.0);
+        bld.connect_place_with_transition(ip1, t1);
+        let p1 = bld.add_place(1.0);
+        bld.set_place_name(p1, "mixed");
+        bld.connect_transition_with_place(t1, p1);
+        let handler = consumer_factory.create_handler_for(t1);
+        assert_eq!(bld.add_action_for_named_out_trans("mixer", handler),
+                   Err(HandlerError::NotAnOutTransition(t1)));
+        let (net, event_manager) = bld.build().unwrap();
+
+        assert_eq!(net.named_inputs(vec![("temp_out", UnifiedToken::Phi)]),
+                   Err(NamedInputError::NoSuchPlace("temp_out".to_string())));
+        assert_eq!(net.named_inputs(vec![("temp_in", UnifiedToken::Phi), ("mixed", UnifiedToken::Phi)]),
+                   Err(NamedInputError::NotAnInputPlace("mixed".to_string())));
+        let inps = net.named_inputs(vec![("temp_in", UnifiedToken::from_val(0.5))]).unwrap();
+        assert_eq!(inps, vec![(ip0, UnifiedToken::from_val(0.5))]);
+        let mut exec = SynchronousUnifiedPetriExecutor::new(net, event_manager);
+        exec.run_tick(inps).unwrap();
+        assert_eq!(vec![(ot0, UnifiedToken::Exist(0.5))], consumer_factory.get_current_hist());
+    }
+
 
 }
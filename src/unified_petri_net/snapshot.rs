@@ -0,0 +1,191 @@
+extern crate rustc_serialize;
+
+use basic::*;
+use std::fmt;
+use std::io;
+use std::io::{Read, Write};
+use std::fs::File;
+use std::collections::BTreeMap;
+use self::rustc_serialize::json::{Json, ToJson};
+
+use unified_petri_net::net_builder::UnifiedPetriNet;
+use unified_petri_net::trace::token_json;
+
+/// The mutable part of an executor: the marking, how many tokens were merged into each place,
+/// the remaining delay of every transition, the output tokens the delayed transitions still hold
+/// and where the conflict policy is in its turns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutorSnapshot {
+    pub place_state: Vec<UnifiedToken>,
+    pub place_arrivals: Vec<u32>,
+    pub trans_state: Vec<i32>,
+    pub trans_holds: Vec<Vec<UnifiedToken>>,
+    /// the winner of the previous conflict, round robin goes on after it
+    pub last_winner: Option<usize>,
+    /// the state of the seeded random policy, 0 leaves the executor's own state
+    pub rng_state: u64,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    WrongJson(String),
+    ShapeMismatch(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SnapshotError::Io(ref err) => write!(f, "snapshot io error: {}", err),
+            SnapshotError::WrongJson(ref msg) => write!(f, "wrong snapshot json: {}", msg),
+            SnapshotError::ShapeMismatch(ref msg) => write!(f, "snapshot does not fit the net: {}", msg),
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> SnapshotError {
+        SnapshotError::Io(err)
+    }
+}
+
+static PLACE_STATE: &'static str = "placeState";
+static PLACE_ARRIVALS: &'static str = "placeArrivals";
+static TRANS_STATE: &'static str = "transState";
+static TRANS_HOLDS: &'static str = "transHolds";
+static LAST_WINNER: &'static str = "lastWinner";
+static RNG_STATE: &'static str = "rngState";
+
+impl ExecutorSnapshot {
+
+    pub fn to_json(&self) -> String {
+        let mut obj = BTreeMap::new();
+        obj.insert(PLACE_STATE.to_string(), Json::Array(self.place_state.iter().map(token_json).collect()));
+        obj.insert(PLACE_ARRIVALS.to_string(), self.place_arrivals.to_json());
+        obj.insert(TRANS_STATE.to_string(), self.trans_state.to_json());
+        obj.insert(TRANS_HOLDS.to_string(), Json::Array(self.trans_holds.iter()
+            .map(|holds| Json::Array(holds.iter().map(token_json).collect()))
+            .collect()));
+        obj.insert(LAST_WINNER.to_string(), self.last_winner.to_json());
+        obj.insert(RNG_STATE.to_string(), self.rng_state.to_json());
+        format!("{}", Json::Object(obj))
+    }
+
+    pub fn from_json(what: &str) -> Result<ExecutorSnapshot, SnapshotError> {
+        let data = Json::from_str(what).map_err(|err| SnapshotError::WrongJson(format!("{}", err)))?;
+        let obj = data.as_object().ok_or_else(|| SnapshotError::WrongJson("expected object".to_string()))?;
+        let array = |key: &'static str| obj.get(key).and_then(|js| js.as_array())
+            .ok_or_else(|| SnapshotError::WrongJson(format!("{} array not found", key)));
+
+        let place_state = read_tokens(array(PLACE_STATE)?, PLACE_STATE)?;
+        // snapshots from before the merge strategies count every token as one arrival
+        let place_arrivals = match obj.get(PLACE_ARRIVALS) {
+            None => place_state.iter().map(|tk| if tk.not_phi() { 1 } else { 0 }).collect(),
+            Some(_) => {
+                let mut arrivals = vec![];
+                for (pl_id, js) in array(PLACE_ARRIVALS)?.iter().enumerate() {
+                    let nr = js.as_u64()
+                        .ok_or_else(|| SnapshotError::WrongJson(format!("{}[{}] is not a count", PLACE_ARRIVALS, pl_id)))?;
+                    arrivals.push(nr as u32);
+                }
+                arrivals
+            }
+        };
+        let mut trans_state = vec![];
+        for (tr_id, js) in array(TRANS_STATE)?.iter().enumerate() {
+            let delay = js.as_i64()
+                .ok_or_else(|| SnapshotError::WrongJson(format!("{}[{}] is not a number", TRANS_STATE, tr_id)))?;
+            trans_state.push(delay as i32);
+        }
+        let mut trans_holds = vec![];
+        for (tr_id, js) in array(TRANS_HOLDS)?.iter().enumerate() {
+            let holds = js.as_array()
+                .ok_or_else(|| SnapshotError::WrongJson(format!("{}[{}] is not an array", TRANS_HOLDS, tr_id)))?;
+            trans_holds.push(read_tokens(holds, TRANS_HOLDS)?);
+        }
+        // snapshots from before the conflict policies have no turns to go on with
+        let last_winner = match obj.get(LAST_WINNER) {
+            None | Some(&Json::Null) => None,
+            Some(js) => Some(js.as_u64()
+                .ok_or_else(|| SnapshotError::WrongJson(format!("{} is not a transition id", LAST_WINNER)))? as usize),
+        };
+        let rng_state = match obj.get(RNG_STATE) {
+            None => 0,
+            Some(js) => js.as_u64()
+                .ok_or_else(|| SnapshotError::WrongJson(format!("{} is not a number", RNG_STATE)))?,
+        };
+        Ok(ExecutorSnapshot {
+            place_state: place_state,
+            place_arrivals: place_arrivals,
+            trans_state: trans_state,
+            trans_holds: trans_holds,
+            last_winner: last_winner,
+            rng_state: rng_state,
+        })
+    }
+
+    pub fn write_to_file(&self, fname: &str) -> io::Result<()> {
+        let mut f = File::create(fname)?;
+        f.write_all(self.to_json().as_bytes())?;
+        Ok(())
+    }
+
+    pub fn read_from_file(fname: &str) -> Result<ExecutorSnapshot, SnapshotError> {
+        let mut f = File::open(fname)?;
+        let mut s = String::new();
+        f.read_to_string(&mut s)?;
+        ExecutorSnapshot::from_json(&s)
+    }
+
+    /// A snapshot fits the net if it has a state for every node,
+    /// and only the delayed transitions hold as many tokens as their table produces
+    pub fn check_shape(&self, net: &UnifiedPetriNet) -> Result<(), SnapshotError> {
+        let mismatch = |msg: String| Err(SnapshotError::ShapeMismatch(msg));
+        if self.place_state.len() != net.get_place_nr() || self.place_arrivals.len() != net.get_place_nr() {
+            return mismatch(format!("{} places and {} arrival counts in the snapshot, {} places in the net",
+                                    self.place_state.len(), self.place_arrivals.len(), net.get_place_nr()));
+        }
+        if self.trans_state.len() != net.get_trans_nr() || self.trans_holds.len() != net.get_trans_nr() {
+            return mismatch(format!("{} transition states and {} holds in the snapshot, {} transitions in the net",
+                                    self.trans_state.len(), self.trans_holds.len(), net.get_trans_nr()));
+        }
+        if let Some(tr_id) = self.last_winner {
+            if tr_id >= net.get_trans_nr() {
+                return mismatch(format!("the last conflict winner T{} is not in the net", tr_id));
+            }
+        }
+        for tr_id in 0..net.get_trans_nr() {
+            let holds = self.trans_holds[tr_id].len();
+            if self.trans_state[tr_id] < 0 {
+                return mismatch(format!("{} has negative delay", net.trans_label(tr_id)));
+            }
+            if self.trans_state[tr_id] == 0 {
+                if holds != 0 {
+                    return mismatch(format!("{} is not delayed but holds tokens", net.trans_label(tr_id)));
+                }
+                continue;
+            }
+            let expected = net.typed_table_for_trans(tr_id).output_nr();
+            if holds != expected {
+                return mismatch(format!("{} holds {} tokens, its table produces {}",
+                                        net.trans_label(tr_id), holds, expected));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_tokens(jsons: &Vec<Json>, talking_about: &str) -> Result<Vec<UnifiedToken>, SnapshotError> {
+    let mut to_ret = vec![];
+    for js in jsons {
+        match *js {
+            Json::Null => to_ret.push(UnifiedToken::Phi),
+            _ => {
+                let v = js.as_f64()
+                    .ok_or_else(|| SnapshotError::WrongJson(format!("{} has a token that is not a number", talking_about)))?;
+                to_ret.push(UnifiedToken::from_val(v as f32));
+            }
+        }
+    }
+    Ok(to_ret)
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+/// Counters an executor keeps since it was created, a reset does not clear them
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExecutorStatistics {
+    pub ticks: u64,
+    /// ticks that arrived while the executor was paused, they were dropped and no delay advanced
+    pub paused_ticks: u64,
+    pub input_tokens: u64,
+    /// inputs not addressed to an input place, whether the policy rejected or ignored them
+    pub rejected_inputs: u64,
+    pub firings: u64,
+    pub output_tokens: u64,
+    pub loop_limit_hits: u64,
+    /// faulty firings, whatever the fault policy did with them
+    pub faults: u64,
+    /// the most transitions fired after one tick or input, `max_loop` means the limit was hit
+    pub max_loop_iterations: usize,
+    /// lookups of the possibly executable transitions for a marking
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// wall time of the ticks, the debugger counts only the time spent in its steps
+    pub last_tick_time: Duration,
+    pub max_tick_time: Duration,
+    pub total_tick_time: Duration,
+    /// indexed by transition id
+    pub transitions: Vec<TransitionStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TransitionStatistics {
+    pub firings: u64,
+    /// ticks it started busy with a delayed firing
+    pub delay_ticks: u64,
+    /// times its input places were marked enough but its table did not fire
+    pub not_fireable: u64,
+}
+
+impl ExecutorStatistics {
+    pub fn new() -> ExecutorStatistics {
+        ExecutorStatistics::default()
+    }
+
+    pub fn for_transitions(trans_nr: usize) -> ExecutorStatistics {
+        ExecutorStatistics {
+            transitions: vec![TransitionStatistics::default(); trans_nr],
+            ..ExecutorStatistics::default()
+        }
+    }
+
+    /// 0 before the first lookup
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let lookups = self.cache_hits + self.cache_misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.cache_hits as f64 / lookups as f64
+    }
+
+    pub fn mean_tick_time(&self) -> Duration {
+        if self.ticks == 0 {
+            return Duration::from_secs(0);
+        }
+        Duration::from_secs_f64(self.total_tick_time.as_secs_f64() / self.ticks as f64)
+    }
+
+    /// The transitions that never fired, candidates for dead rules
+    pub fn never_fired(&self) -> Vec<usize> {
+        self.transitions.iter().enumerate()
+            .filter(|&(_, tr)| tr.firings == 0)
+            .map(|(tr_id, _)| tr_id)
+            .collect()
+    }
+
+    pub fn add_tick_time(&mut self, time: Duration) {
+        self.last_tick_time = time;
+        if time > self.max_tick_time {
+            self.max_tick_time = time;
+        }
+        self.total_tick_time += time;
+    }
+}
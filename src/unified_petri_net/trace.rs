@@ -0,0 +1,202 @@
+extern crate rustc_serialize;
+
+use basic::*;
+use std::io::Write;
+use std::collections::BTreeMap;
+use self::rustc_serialize::json::{Json, ToJson};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringStart {
+    pub tr_id: usize,
+    pub inputs: Vec<UnifiedToken>,
+    pub outputs: Vec<UnifiedToken>,
+    pub delay: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringFinish {
+    pub tr_id: usize,
+    pub outputs: Vec<UnifiedToken>,
+}
+
+/// Several transitions were fireable with the same priority, the conflict policy chose one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictChoice {
+    pub candidates: Vec<usize>,
+    pub chosen: usize,
+}
+
+/// What happened during one step of an executor: a tick, or an input message of the async executors.
+/// `marking` and `holds` are taken at the end of the step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickTrace {
+    pub tick: u64,
+    pub policy: String,
+    pub inputs: Vec<(usize, UnifiedToken)>,
+    pub conflicts: Vec<ConflictChoice>,
+    pub started: Vec<FiringStart>,
+    pub finished: Vec<FiringFinish>,
+    pub marking: Vec<UnifiedToken>,
+    pub holds: Vec<(usize, Vec<UnifiedToken>)>,
+}
+
+impl TickTrace {
+    fn new(tick: u64, policy: &str) -> TickTrace {
+        TickTrace {
+            tick: tick,
+            policy: policy.to_string(),
+            inputs: vec![],
+            conflicts: vec![],
+            started: vec![],
+            finished: vec![],
+            marking: vec![],
+            holds: vec![],
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("tick".to_string(), self.tick.to_json());
+        obj.insert("policy".to_string(), self.policy.to_json());
+        obj.insert("conflicts".to_string(), Json::Array(self.conflicts.iter()
+            .map(|conflict| {
+                let mut conflict_obj = BTreeMap::new();
+                conflict_obj.insert("candidates".to_string(), conflict.candidates.to_json());
+                conflict_obj.insert("chosen".to_string(), conflict.chosen.to_json());
+                Json::Object(conflict_obj)
+            })
+            .collect()));
+        obj.insert("inputs".to_string(), Json::Array(self.inputs.iter()
+            .map(|&(pl_id, ref tk)| Json::Array(vec![pl_id.to_json(), token_json(tk)]))
+            .collect()));
+        obj.insert("started".to_string(), Json::Array(self.started.iter()
+            .map(|st| {
+                let mut st_obj = BTreeMap::new();
+                st_obj.insert("tr".to_string(), st.tr_id.to_json());
+                st_obj.insert("inputs".to_string(), tokens_json(&st.inputs));
+                st_obj.insert("outputs".to_string(), tokens_json(&st.outputs));
+                st_obj.insert("delay".to_string(), st.delay.to_json());
+                Json::Object(st_obj)
+            })
+            .collect()));
+        obj.insert("finished".to_string(), Json::Array(self.finished.iter()
+            .map(|fin| {
+                let mut fin_obj = BTreeMap::new();
+                fin_obj.insert("tr".to_string(), fin.tr_id.to_json());
+                fin_obj.insert("outputs".to_string(), tokens_json(&fin.outputs));
+                Json::Object(fin_obj)
+            })
+            .collect()));
+        obj.insert("marking".to_string(), tokens_json(&self.marking));
+        obj.insert("holds".to_string(), Json::Array(self.holds.iter()
+            .map(|&(tr_id, ref tks)| Json::Array(vec![tr_id.to_json(), tokens_json(tks)]))
+            .collect()));
+        Json::Object(obj)
+    }
+
+    /// One line of the JSON Lines export, without the line break
+    pub fn to_json_line(&self) -> String {
+        format!("{}", self.to_json())
+    }
+}
+
+pub fn token_json(tk: &UnifiedToken) -> Json {
+    match *tk {
+        UnifiedToken::Phi => Json::Null,
+        UnifiedToken::Exist(v) => (v as f64).to_json(),
+    }
+}
+
+fn tokens_json(tks: &Vec<UnifiedToken>) -> Json {
+    Json::Array(tks.iter().map(token_json).collect())
+}
+
+pub fn write_json_lines(traces: &[TickTrace], w: &mut Write) -> ::std::io::Result<()> {
+    for trace in traces {
+        writeln!(w, "{}", trace.to_json_line())?;
+    }
+    Ok(())
+}
+
+/// Collects the trace of the current step, finished steps are kept in memory
+/// or, when a sink is given, streamed to it as JSON Lines.
+/// A sink that fails to write is dropped and the recorder falls back to memory,
+/// starting with the step it failed on.
+pub struct TraceRecorder {
+    current: TickTrace,
+    records: Vec<TickTrace>,
+    sink: Option<Box<Write + Send>>,
+    policy: String,
+}
+
+impl TraceRecorder {
+    pub fn in_memory() -> TraceRecorder {
+        TraceRecorder {
+            current: TickTrace::new(0, ""),
+            records: vec![],
+            sink: None,
+            policy: String::new(),
+        }
+    }
+
+    pub fn streaming(sink: Box<Write + Send>) -> TraceRecorder {
+        TraceRecorder {
+            current: TickTrace::new(0, ""),
+            records: vec![],
+            sink: Some(sink),
+            policy: String::new(),
+        }
+    }
+
+    pub fn record_inputs(&mut self, inps: &Vec<(usize, UnifiedToken)>) {
+        self.current.inputs.extend(inps.iter().cloned());
+    }
+
+    /// The executor tells the conflict policy it uses, the current step gets it too
+    pub fn set_policy(&mut self, policy: &str) {
+        self.policy = policy.to_string();
+        self.current.policy = self.policy.clone();
+    }
+
+    pub fn record_conflict(&mut self, candidates: Vec<usize>, chosen: usize) {
+        self.current.conflicts.push(ConflictChoice{candidates: candidates, chosen: chosen});
+    }
+
+    pub fn record_start(&mut self, start: FiringStart) {
+        self.current.started.push(start);
+    }
+
+    pub fn record_finish(&mut self, tr_id: usize, outputs: Vec<UnifiedToken>) {
+        self.current.finished.push(FiringFinish{tr_id: tr_id, outputs: outputs});
+    }
+
+    /// Closes the current step, the next one gets the next tick number only if `tick_ended`
+    pub fn end_step(&mut self, marking: &Vec<UnifiedToken>, holds: &Vec<Vec<UnifiedToken>>,
+                    trans_state: &Vec<i32>, tick_ended: bool) {
+        let next_tick = if tick_ended { self.current.tick + 1 } else { self.current.tick };
+        let mut done = ::std::mem::replace(&mut self.current, TickTrace::new(next_tick, &self.policy));
+        done.marking = marking.clone();
+        done.holds = (0..holds.len())
+            .filter(|tr_id| trans_state[*tr_id] > 0)
+            .map(|tr_id| (tr_id, holds[tr_id].clone()))
+            .collect();
+        let written = match self.sink {
+            Some(ref mut sink) => writeln!(sink, "{}", done.to_json_line()).is_ok(),
+            None => false,
+        };
+        if !written {
+            self.sink = None;
+            self.records.push(done);
+        }
+    }
+
+    /// The step being recorded
+    pub fn current(&self) -> &TickTrace {
+        &self.current
+    }
+
+    /// The steps recorded in memory since the last call
+    pub fn take(&mut self) -> Vec<TickTrace> {
+        ::std::mem::replace(&mut self.records, vec![])
+    }
+}
@@ -62,12 +62,12 @@ impl ConsumerFactory {
 
 #[test]
 fn controller_net_test(){
-    let ww = my_file_read("unified_nets/controller.json");
+    let ww = my_file_read("unified_nets/controller.json").unwrap();
     let rez = deseralize(&ww);
     let bld = rez.unwrap();
 
 
-    let (net, mut man) = bld.build();
+    let (net, mut man) = bld.build().unwrap();
 
     let mut consumer_fact = ConsumerFactory::new();
     consumer_fact.create_handler_for_all_outs(&net, &mut man );
@@ -80,7 +80,7 @@ fn controller_net_test(){
         (1, UnifiedToken::from_val(10.0)),
         (0, UnifiedToken::from_val(40.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(10.000001)), (1, UnifiedToken::Exist(20.0))],
                current_hist);
@@ -88,12 +88,12 @@ fn controller_net_test(){
 
 #[test]
 fn lane() {
-    let ww = my_file_read("unified_nets/lane.json");
+    let ww = my_file_read("unified_nets/lane.json").unwrap();
     let rez = deseralize(&ww);
     let bld = rez.unwrap();
 
 
-    let (net, mut man) = bld.build();
+    let (net, mut man) = bld.build().unwrap();
 
     let mut consumer_fact = ConsumerFactory::new();
     consumer_fact.create_handler_for_all_outs(&net, &mut man );
@@ -102,7 +102,7 @@ fn lane() {
     let inp = vec![
         (2, UnifiedToken::from_val(0.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(0.0))], current_hist);
     consumer_fact.clear_history();
@@ -110,14 +110,14 @@ fn lane() {
     let inp = vec![
         (1, UnifiedToken::from_val(10.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert!( current_hist.is_empty());
 
     let inp = vec![
         (2, UnifiedToken::from_val(0.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(10.0))], current_hist);
     consumer_fact.clear_history();
@@ -125,14 +125,14 @@ fn lane() {
     let inp = vec![
         (0, UnifiedToken::from_val(5.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert!( current_hist.is_empty());
 
     let inp = vec![
         (2, UnifiedToken::from_val(0.0)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(5.0))], current_hist);
 
@@ -142,12 +142,12 @@ fn lane() {
 
 #[test]
 fn max_finder() {
-    let ww = my_file_read("unified_nets/maxTableTryOut.json");
+    let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
     let rez = deseralize(&ww);
     let bld = rez.unwrap();
 
 
-    let (net, mut man) = bld.build();
+    let (net, mut man) = bld.build().unwrap();
 
     let mut consumer_fact = ConsumerFactory::new();
     consumer_fact.create_handler_for_all_outs(&net, &mut man );
@@ -157,7 +157,7 @@ fn max_finder() {
         (0, UnifiedToken::from_val(0.0)),
         (1, UnifiedToken::from_val(0.3)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(0.3))], current_hist);
 
@@ -167,8 +167,27 @@ fn max_finder() {
         (0, UnifiedToken::from_val(0.2)),
         (1, UnifiedToken::from_val(-0.3)),
     ];
-    exec.run_tick(inp);
+    exec.run_tick(inp).unwrap();
     let current_hist =consumer_fact.get_current_hist();
     assert_eq!(vec![(0, UnifiedToken::Exist(0.2))], current_hist);
 
 }
+
+#[test]
+fn max_finder_with_tick_result() {
+    let ww = my_file_read("unified_nets/maxTableTryOut.json").unwrap();
+    let (net, man) = deseralize(&ww).unwrap().build().unwrap();
+
+    let mut exec = SynchronousUnifiedPetriExecutor::new(net, man);
+    let rez = exec.run_tick(vec![
+        (0, UnifiedToken::from_val(0.0)),
+        (1, UnifiedToken::from_val(0.3)),
+    ]).unwrap();
+    assert_eq!(vec![(0, UnifiedToken::Exist(0.3))], rez.outputs);
+
+    let rez = exec.run_tick(vec![
+        (0, UnifiedToken::from_val(0.2)),
+        (1, UnifiedToken::from_val(-0.3)),
+    ]).unwrap();
+    assert_eq!(vec![(0, UnifiedToken::Exist(0.2))], rez.outputs);
+}
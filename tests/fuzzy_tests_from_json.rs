@@ -0,0 +1,95 @@
+use std::sync::*;
+
+extern crate ruzz_p;
+use ruzz_p::read_petri::{my_file_read, deseralize_fuzzy};
+use ruzz_p::fuzzy_petri_net::*;
+use ruzz_p::basic::*;
+
+struct History{
+    rez : Vec<(usize,FuzzyToken)>,
+}
+
+struct MyConsumer {
+    hist: Arc<RwLock<History>>,
+    tr_id : usize,
+}
+
+impl FuzzyTokenConsumer for MyConsumer {
+    fn consume(&mut self, ft: FuzzyToken){
+        self.hist.write().unwrap().rez.push((self.tr_id, ft));
+    }
+}
+
+pub struct ConsumerFactory {
+    hist: Arc<RwLock<History>>,
+}
+
+impl ConsumerFactory {
+    fn new()-> ConsumerFactory{
+        let hist = History{rez: Vec::new()};
+        ConsumerFactory{
+            hist: Arc::new(RwLock::new(hist)),
+        }
+    }
+
+    fn create_handler_for(&mut self, tr_id :usize ) -> Box<MyConsumer> {
+        Box::new(MyConsumer{hist : self.hist.clone(), tr_id: tr_id})
+    }
+
+    pub fn get_current_hist(&self) -> Vec<(usize, FuzzyToken)> {
+        let mut to_ret = Vec::new();
+        for i in &self.hist.read().unwrap().rez {
+            to_ret.push(i.clone());
+        }
+        to_ret
+    }
+
+    pub fn clear_history(&self)  {
+        self.hist.write().unwrap().rez.clear();
+    }
+
+    pub fn create_handler_for_all_outs(&mut self,
+                                   net: &FuzzyPetriNet, manager: &mut FuzzyEventManager ){
+        for tr_id in 0..net.get_trans_nr() {
+            if net.is_trans_out(tr_id) {
+                manager.add(tr_id, self.create_handler_for(tr_id));
+            }
+        }
+
+    }
+
+}
+
+macro_rules! t {
+    ($fv1:expr, $fv2:expr,$fv3:expr,$fv4:expr,$fv5:expr) =>  (
+        FuzzyToken::from_arr([$fv1, $fv2, $fv3, $fv4, $fv5])
+    )
+}
+
+#[test]
+fn comparator_test(){
+    let ww = my_file_read("inputs/Comparator.json").unwrap();
+    let rez = deseralize_fuzzy(&ww);
+    let bld = rez.unwrap();
+
+    let (net, mut man) = bld.build().unwrap();
+
+    let mut consumer_fact = ConsumerFactory::new();
+    consumer_fact.create_handler_for_all_outs(&net, &mut man );
+
+    let mut exec = SynchronousFuzzyPetriExecutor::new(net, man);
+    let inp = vec![
+        (0, t!(0.0, 0.0, 0.0, 0.0, 1.0)),
+        (1, t!(1.0, 0.0, 0.0, 0.0, 0.0)),
+    ];
+    exec.run_tick(inp);
+    assert_eq!(vec![(3, FuzzyToken::zero_token())], consumer_fact.get_current_hist());
+    consumer_fact.clear_history();
+
+    let inp = vec![
+        (0, t!(1.0, 0.0, 0.0, 0.0, 0.0)),
+        (1, t!(0.0, 0.0, 0.0, 0.0, 1.0)),
+    ];
+    exec.run_tick(inp);
+    assert_eq!(vec![(2, FuzzyToken::zero_token())], consumer_fact.get_current_hist());
+}